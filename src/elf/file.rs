@@ -6,7 +6,7 @@ use byteorder;
 use byteorder::ReadBytesExt;
 use elf::types;
 use std::collections::HashMap;
-use {Error, Section, Object};
+use {read_file, Error, FileData, Section, Object};
 
 macro_rules! read_u8 {
     ($data:ident, $io:ident) => (
@@ -44,7 +44,7 @@ macro_rules! read_u64 {
     );
 }
 
-fn get_elf_string(data: &Vec<u8>, start: usize) -> String {
+fn get_elf_string(data: &[u8], start: usize) -> String {
     let mut end = 0usize;
     for i in start..data.len() {
         if data[i] == 0u8 {
@@ -61,10 +61,68 @@ fn get_elf_string(data: &Vec<u8>, start: usize) -> String {
     ret
 }
 
+/// Convert an ELF symbol to the format independent representation
+fn common_symbol(sym: &types::Symbol, sections: &[types::SectionHeader]) -> ::Symbol {
+    let kind = match sym.sym_type() {
+        _ if sym.shndx == types::SHN_COMMON => ::SymbolKind::Common,
+        types::STT_FUNC | types::STT_GNU_IFUNC => ::SymbolKind::Text,
+        types::STT_OBJECT => ::SymbolKind::Data,
+        types::STT_SECTION => ::SymbolKind::Section,
+        types::STT_FILE => ::SymbolKind::File,
+        types::STT_COMMON => ::SymbolKind::Common,
+        types::STT_TLS => ::SymbolKind::Tls,
+        _ => ::SymbolKind::Unknown,
+    };
+    let binding = match sym.bind() {
+        types::STB_LOCAL => ::SymbolBinding::Local,
+        types::STB_WEAK => ::SymbolBinding::Weak,
+        _ => ::SymbolBinding::Global,
+    };
+    let visibility = match sym.visibility() {
+        types::STV_INTERNAL => ::SymbolVisibility::Internal,
+        types::STV_HIDDEN => ::SymbolVisibility::Hidden,
+        types::STV_PROTECTED => ::SymbolVisibility::Protected,
+        _ => ::SymbolVisibility::Default,
+    };
+    let section = match sym.shndx {
+        types::SHN_UNDEF | types::SHN_ABS | types::SHN_COMMON | types::SHN_XINDEX => None,
+        x => sections.get(x as usize).map(|x| x.name.clone()),
+    };
+    ::Symbol {
+        name: sym.name.clone(),
+        address: sym.value,
+        size: sym.size,
+        kind: kind,
+        binding: binding,
+        visibility: visibility,
+        defined: sym.shndx != types::SHN_UNDEF,
+        section: section,
+    }
+}
+
 pub struct File {
     pub hdr: types::FileHeader,
-    pub sections: HashMap<String, Section>,
+    /// Sections in section header order
+    pub sections: Vec<Section>,
     pub symbols: HashMap<String, u64>,
+    /// .symtab entries, without the null symbol
+    pub symtab: Vec<types::Symbol>,
+    /// .dynsym entries, without the null symbol
+    pub dynsym: Vec<types::Symbol>,
+    pub phdrs: Vec<types::ProgramHeader>,
+    /// Dynamic section entries, up to DT_NULL
+    pub dynamic: Vec<types::Dynamic>,
+    /// DT_NEEDED library names
+    pub needed: Vec<String>,
+    /// .gnu.version entries, parallel to `dynsym`
+    pub versym: Vec<u16>,
+    pub verdef: Vec<types::VersionDef>,
+    pub verneed: Vec<types::VersionNeed>,
+    common_symbols: Vec<::Symbol>,
+    segments: Vec<::Segment>,
+    imports: Vec<::Import>,
+    exports: Vec<::Export>,
+    relocations: Vec<::Relocation>,
 }
 
 impl File {
@@ -113,7 +171,61 @@ impl File {
         let shnum = try!(read_u16!(data, r));
         let shstrndx = try!(read_u16!(data, r));
 
-        let mut sections = HashMap::new();
+        let mut phdrs = Vec::new();
+        for i in 0..phnum as u64 {
+            try!(r.seek(io::SeekFrom::Start(phoff + i * phentsize as u64)));
+            let progtype = types::ProgType(try!(read_u32!(data, r)));
+            let (offset, vaddr, paddr, filesz, memsz, flags, align);
+            match class {
+                types::ELFCLASS32 => {
+                    offset = try!(read_u32!(data, r)) as u64;
+                    vaddr = try!(read_u32!(data, r)) as u64;
+                    paddr = try!(read_u32!(data, r)) as u64;
+                    filesz = try!(read_u32!(data, r)) as u64;
+                    memsz = try!(read_u32!(data, r)) as u64;
+                    flags = types::ProgFlag(try!(read_u32!(data, r)));
+                    align = try!(read_u32!(data, r)) as u64;
+                }
+                types::ELFCLASS64 => {
+                    flags = types::ProgFlag(try!(read_u32!(data, r)));
+                    offset = try!(read_u64!(data, r));
+                    vaddr = try!(read_u64!(data, r));
+                    paddr = try!(read_u64!(data, r));
+                    filesz = try!(read_u64!(data, r));
+                    memsz = try!(read_u64!(data, r));
+                    align = try!(read_u64!(data, r));
+                }
+                _ => unreachable!(),
+            }
+            phdrs.push(types::ProgramHeader {
+                progtype: progtype,
+                offset: offset,
+                vaddr: vaddr,
+                paddr: paddr,
+                filesz: filesz,
+                memsz: memsz,
+                flags: flags,
+                align: align,
+            });
+        }
+
+        let file_data = try!(read_file(r));
+        let mut segments = Vec::new();
+        for phdr in phdrs.iter().filter(|x| x.progtype == types::PT_LOAD) {
+            segments.push(::Segment {
+                name: None,
+                addr: phdr.vaddr,
+                size: phdr.memsz,
+                offset: phdr.offset,
+                file_size: phdr.filesz,
+                read: phdr.flags.0 & types::PF_R.0 != 0,
+                write: phdr.flags.0 & types::PF_W.0 != 0,
+                execute: phdr.flags.0 & types::PF_X.0 != 0,
+                data: FileData::new(&file_data, phdr.offset, phdr.filesz),
+            });
+        }
+
+        let mut sections = Vec::new();
         let mut sections_lst = Vec::new();
         let mut sections_data = Vec::new();
 
@@ -173,47 +285,319 @@ impl File {
         }
 
         for i in 0..shnum {
-            let off = sections_lst[i as usize].offset;
-            let size = sections_lst[i as usize].size;
-            try!(r.seek(io::SeekFrom::Start(off)));
-            let data: Vec<u8> = io::Read::by_ref(r).bytes().map(|x| x.unwrap()).take(size as usize).collect();
-            sections_data.push(data);
+            let shdr = &sections_lst[i as usize];
+            // SHT_NOBITS sections occupy memory but have no bytes in the file
+            sections_data.push(if shdr.shtype == types::SHT_NOBITS {
+                FileData::default()
+            } else {
+                FileData::new(&file_data, shdr.offset, shdr.size)
+            });
         }
 
-        let mut symbols = HashMap::new();
+        for i in 0..shnum {
+            sections_lst[i as usize].name = get_elf_string(&sections_data[shstrndx as usize], name_idxs[i as usize] as usize);
+        }
 
+        let mut symtab = Vec::new();
+        let mut dynsym = Vec::new();
         for (i, section) in sections_lst.iter().enumerate() {
-            if section.shtype == types::SHT_SYMTAB {
-                let mut cur = io::Cursor::new(sections_data[i].as_slice());
-                for i in 0..(section.size / section.entsize) {
-                    try!(cur.seek(io::SeekFrom::Start(i * section.entsize)));
-                    let sym_name;
-                    let sym_addr;
-                    match class {
-                        types::ELFCLASS32 => {
-                            sym_name = try!(read_u32!(data, cur));
-                            sym_addr = try!(read_u32!(data, cur)) as u64;
-                        }
-                        types::ELFCLASS64 => {
-                            sym_name = try!(read_u32!(data, cur));
-                            let _ = try!(read_u8!(data, cur));
-                            let _ = try!(read_u8!(data, cur));
-                            let _ = try!(read_u16!(data, cur));
-                            sym_addr = try!(read_u64!(data, cur));
-                        }
-                        _ => unreachable!(),
+            if section.shtype != types::SHT_SYMTAB && section.shtype != types::SHT_DYNSYM {
+                continue;
+            }
+            let strtab = match sections_data.get(section.link as usize) {
+                Some(x) => x,
+                None => return Err(Box::new(Error::from("invalid symbol string table index"))),
+            };
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            let count = if section.entsize == 0 { 0 } else { sections_data[i].len() as u64 / section.entsize };
+            // Entry 0 is the reserved null symbol
+            for j in 1..count {
+                try!(cur.seek(io::SeekFrom::Start(j * section.entsize)));
+                let (sym_name, value, size, info, other, shndx);
+                match class {
+                    types::ELFCLASS32 => {
+                        sym_name = try!(read_u32!(data, cur));
+                        value = try!(read_u32!(data, cur)) as u64;
+                        size = try!(read_u32!(data, cur)) as u64;
+                        info = try!(read_u8!(data, cur));
+                        other = try!(read_u8!(data, cur));
+                        shndx = try!(read_u16!(data, cur));
+                    }
+                    types::ELFCLASS64 => {
+                        sym_name = try!(read_u32!(data, cur));
+                        info = try!(read_u8!(data, cur));
+                        other = try!(read_u8!(data, cur));
+                        shndx = try!(read_u16!(data, cur));
+                        value = try!(read_u64!(data, cur));
+                        size = try!(read_u64!(data, cur));
                     }
-                    symbols.insert(get_elf_string(&sections_data[section.link as usize], sym_name as usize), sym_addr);
+                    _ => unreachable!(),
+                }
+                let sym = types::Symbol {
+                    name: get_elf_string(strtab, sym_name as usize),
+                    value: value,
+                    size: size,
+                    info: info,
+                    other: other,
+                    shndx: shndx,
+                };
+                if section.shtype == types::SHT_SYMTAB {
+                    symtab.push(sym);
+                } else {
+                    dynsym.push(sym);
                 }
             }
         }
 
-        for i in 0..shnum {
-            sections_lst[i as usize].name = get_elf_string(&sections_data[shstrndx as usize], name_idxs[i as usize] as usize);
+        let mut dynamic = Vec::new();
+        if let Some(i) = sections_lst.iter().position(|x| x.shtype == types::SHT_DYNAMIC) {
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            let entsize = if class == types::ELFCLASS32 { 8 } else { 16 };
+            for _ in 0..sections_data[i].len() / entsize {
+                let (tag, val) = match class {
+                    types::ELFCLASS32 => (try!(read_u32!(data, cur)) as u64, try!(read_u32!(data, cur)) as u64),
+                    _ => (try!(read_u64!(data, cur)), try!(read_u64!(data, cur))),
+                };
+                if tag == types::DT_NULL {
+                    break;
+                }
+                dynamic.push(types::Dynamic {
+                    tag: tag,
+                    val: val,
+                });
+            }
+        }
+
+        let mut needed = Vec::new();
+        if let Some(i) = sections_lst.iter().position(|x| x.shtype == types::SHT_DYNAMIC) {
+            if let Some(strtab) = sections_data.get(sections_lst[i].link as usize) {
+                for entry in dynamic.iter().filter(|x| x.tag == types::DT_NEEDED) {
+                    needed.push(get_elf_string(strtab, entry.val as usize));
+                }
+            }
+        }
+
+        let mut versym = Vec::new();
+        if let Some(i) = sections_lst.iter().position(|x| x.shtype == types::SHT_GNU_VERSYM) {
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            // Entry 0 belongs to the null symbol
+            for j in 0..sections_data[i].len() / 2 {
+                let x = try!(read_u16!(data, cur));
+                if j > 0 {
+                    versym.push(x);
+                }
+            }
+        }
+
+        let mut verdef = Vec::new();
+        if let Some(i) = sections_lst.iter().position(|x| x.shtype == types::SHT_GNU_VERDEF) {
+            let strtab = match sections_data.get(sections_lst[i].link as usize) {
+                Some(x) => x,
+                None => return Err(Box::new(Error::from("invalid version string table index"))),
+            };
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            let mut off = 0u64;
+            // sh_info holds the number of entries
+            for _ in 0..sections_lst[i].info {
+                try!(cur.seek(io::SeekFrom::Start(off)));
+                let vd_version = try!(read_u16!(data, cur));
+                let vd_flags = try!(read_u16!(data, cur));
+                let vd_ndx = try!(read_u16!(data, cur));
+                let vd_cnt = try!(read_u16!(data, cur));
+                let vd_hash = try!(read_u32!(data, cur));
+                let vd_aux = try!(read_u32!(data, cur));
+                let vd_next = try!(read_u32!(data, cur));
+                // The first auxiliary entry names the version, any others
+                // its predecessors
+                let mut name = String::new();
+                if vd_cnt > 0 {
+                    try!(cur.seek(io::SeekFrom::Start(off + vd_aux as u64)));
+                    name = get_elf_string(strtab, try!(read_u32!(data, cur)) as usize);
+                }
+                verdef.push(types::VersionDef {
+                    index: vd_ndx,
+                    flags: vd_flags,
+                    name: name,
+                });
+                if vd_next == 0 {
+                    break;
+                }
+                off += vd_next as u64;
+            }
+        }
+
+        let mut verneed = Vec::new();
+        if let Some(i) = sections_lst.iter().position(|x| x.shtype == types::SHT_GNU_VERNEED) {
+            let strtab = match sections_data.get(sections_lst[i].link as usize) {
+                Some(x) => x,
+                None => return Err(Box::new(Error::from("invalid version string table index"))),
+            };
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            let mut off = 0u64;
+            for _ in 0..sections_lst[i].info {
+                try!(cur.seek(io::SeekFrom::Start(off)));
+                let vn_version = try!(read_u16!(data, cur));
+                let vn_cnt = try!(read_u16!(data, cur));
+                let file = get_elf_string(strtab, try!(read_u32!(data, cur)) as usize);
+                let vn_aux = try!(read_u32!(data, cur));
+                let vn_next = try!(read_u32!(data, cur));
+                let mut aux_off = off + vn_aux as u64;
+                for _ in 0..vn_cnt {
+                    try!(cur.seek(io::SeekFrom::Start(aux_off)));
+                    let vna_hash = try!(read_u32!(data, cur));
+                    let vna_flags = try!(read_u16!(data, cur));
+                    let vna_other = try!(read_u16!(data, cur));
+                    let vna_name = try!(read_u32!(data, cur));
+                    let vna_next = try!(read_u32!(data, cur));
+                    verneed.push(types::VersionNeed {
+                        file: file.clone(),
+                        index: vna_other,
+                        flags: vna_flags,
+                        name: get_elf_string(strtab, vna_name as usize),
+                    });
+                    if vna_next == 0 {
+                        break;
+                    }
+                    aux_off += vna_next as u64;
+                }
+                if vn_next == 0 {
+                    break;
+                }
+                off += vn_next as u64;
+            }
+        }
+
+        let mut imports = Vec::new();
+        let mut exports = Vec::new();
+        for (i, sym) in dynsym.iter().enumerate() {
+            let ver = versym.get(i).map(|x| *x & !types::VERSYM_HIDDEN).unwrap_or(types::VER_NDX_GLOBAL);
+            let weak = sym.bind() == types::STB_WEAK;
+            if sym.shndx == types::SHN_UNDEF {
+                let need = verneed.iter().find(|x| x.index == ver);
+                imports.push(::Import {
+                    library: need.map(|x| x.file.clone()),
+                    name: Some(sym.name.clone()),
+                    ordinal: None,
+                    version: need.map(|x| x.name.clone()),
+                    weak: weak || need.map(|x| x.flags & types::VER_FLG_WEAK != 0).unwrap_or(false),
+                    delay: false,
+                    address: None,
+                });
+            } else if sym.bind() != types::STB_LOCAL && ver != types::VER_NDX_LOCAL &&
+                      (sym.visibility() == types::STV_DEFAULT || sym.visibility() == types::STV_PROTECTED) {
+                let def = verdef.iter().find(|x| x.index == ver && x.flags & types::VER_FLG_BASE == 0);
+                exports.push(::Export {
+                    name: Some(sym.name.clone()),
+                    ordinal: None,
+                    address: sym.value,
+                    version: def.map(|x| x.name.clone()),
+                    weak: weak,
+                    forwarder: None,
+                });
+            }
+        }
+
+        let mut relocations = Vec::new();
+        for (i, section) in sections_lst.iter().enumerate() {
+            let is_rela = section.shtype == types::SHT_RELA;
+            if !is_rela && section.shtype != types::SHT_REL {
+                continue;
+            }
+            let syms = match sections_lst.get(section.link as usize) {
+                Some(x) if x.shtype == types::SHT_SYMTAB => &symtab,
+                Some(x) if x.shtype == types::SHT_DYNSYM => &dynsym,
+                _ => &symtab,
+            };
+            let word = if class == types::ELFCLASS32 { 4 } else { 8 };
+            let entsize = match section.entsize {
+                0 => if is_rela { word * 3 } else { word * 2 },
+                x => x,
+            };
+            // In relocatable files r_offset is relative to the section named
+            // by sh_info, elsewhere it is a virtual address
+            let target = if elf_type == types::ET_REL { sections_lst.get(section.info as usize) } else { None };
+            let mut cur = io::Cursor::new(&sections_data[i][..]);
+            for j in 0..sections_data[i].len() as u64 / entsize {
+                try!(cur.seek(io::SeekFrom::Start(j * entsize)));
+                let (offset, sym, r_type, addend);
+                match class {
+                    types::ELFCLASS32 => {
+                        offset = try!(read_u32!(data, cur)) as u64;
+                        let info = try!(read_u32!(data, cur));
+                        sym = info >> 8;
+                        r_type = info & 0xff;
+                        addend = if is_rela { try!(read_u32!(data, cur)) as i32 as i64 } else { 0 };
+                    }
+                    _ => {
+                        offset = try!(read_u64!(data, cur));
+                        let info = try!(read_u64!(data, cur));
+                        sym = (info >> 32) as u32;
+                        r_type = info as u32;
+                        addend = if is_rela { try!(read_u64!(data, cur)) as i64 } else { 0 };
+                    }
+                }
+                let address = target.map(|x| x.addr).unwrap_or(0).wrapping_add(offset);
+                let section_name = match target {
+                    Some(x) => Some(x.name.clone()),
+                    None => sections_lst.iter()
+                        .find(|x| x.flags.0 & types::SHF_ALLOC.0 != 0 && address >= x.addr && address - x.addr < x.size)
+                        .map(|x| x.name.clone()),
+                };
+                // Section symbols have no name of their own
+                let symbol = match syms.get((sym as usize).wrapping_sub(1)) {
+                    Some(x) if x.sym_type() == types::STT_SECTION => {
+                        sections_lst.get(x.shndx as usize).map(|x| x.name.clone())
+                    }
+                    Some(x) => Some(x.name.clone()),
+                    None => None,
+                };
+                relocations.push(::Relocation {
+                    address: address,
+                    section: section_name,
+                    kind: types::relocation_kind(machine, r_type),
+                    raw_type: r_type,
+                    symbol: symbol,
+                    addend: addend,
+                });
+            }
+        }
+
+        let mut symbols = HashMap::new();
+        for sym in symtab.iter() {
+            symbols.insert(sym.name.clone(), sym.value);
+        }
+
+        // Static symbols take precedence; dynamic symbols fill in for
+        // stripped binaries and symbols missing from .symtab
+        let mut common_symbols = Vec::new();
+        {
+            let mut seen = HashMap::new();
+            for sym in symtab.iter().chain(dynsym.iter()) {
+                if seen.insert((sym.name.clone(), sym.value, sym.shndx), ()).is_some() {
+                    continue;
+                }
+                common_symbols.push(common_symbol(sym, &sections_lst));
+            }
         }
 
         for (hdr, data) in sections_lst.into_iter().zip(sections_data.into_iter()) {
-            sections.insert(hdr.name.clone(), Section { name: hdr.name, addr: hdr.addr, offset: hdr.offset, size: hdr.size, data: data });
+            let alloc = hdr.flags.0 & types::SHF_ALLOC.0 != 0;
+            sections.push(Section {
+                name: hdr.name.clone(),
+                addr: hdr.addr,
+                offset: hdr.offset,
+                size: hdr.size,
+                data: data,
+                alloc: alloc,
+                write: hdr.flags.0 & types::SHF_WRITE.0 != 0,
+                execute: hdr.flags.0 & types::SHF_EXECINSTR.0 != 0,
+                bss: hdr.shtype == types::SHT_NOBITS,
+                tls: hdr.flags.0 & types::SHF_TLS.0 != 0,
+                // ELF has no flag for debugging information
+                debug: !alloc && (hdr.name.starts_with(".debug") || hdr.name.starts_with(".zdebug")),
+                align: if hdr.addralign == 0 { 1 } else { hdr.addralign },
+                header: Some(::SectionHeader::Elf(hdr)),
+            });
         }
 
         let x = File {
@@ -229,16 +613,37 @@ impl File {
             },
             sections: sections,
             symbols: symbols,
+            symtab: symtab,
+            dynsym: dynsym,
+            phdrs: phdrs,
+            dynamic: dynamic,
+            needed: needed,
+            versym: versym,
+            verdef: verdef,
+            verneed: verneed,
+            common_symbols: common_symbols,
+            segments: segments,
+            imports: imports,
+            exports: exports,
+            relocations: relocations,
         };
         Ok(x)
     }
 
-    pub fn sections(&self) -> &HashMap<String, Section> {
+    pub fn sections(&self) -> &[Section] {
         &self.sections
     }
     pub fn symbols(&self) -> &HashMap<String, u64> {
         &self.symbols
     }
+    /// Swift types, protocols and conformances from the swift5_* sections
+    pub fn swift_metadata(&self) -> Result<::swift::Metadata, Box<error::Error>> {
+        let sections: Vec<(&str, u64, &[u8])> = self.sections.iter()
+            .map(|x| (x.name(), x.addr(), x.data()))
+            .collect();
+        let ptr_size = if self.hdr.class == types::ELFCLASS32 { 4 } else { 8 };
+        ::swift::parse_swift(&sections, "swift5_", ptr_size)
+    }
 }
 
 impl fmt::Display for File {
@@ -246,7 +651,7 @@ impl fmt::Display for File {
         try!(writeln!(f, "ELF file"));
         try!(write!(f, "{}", self.hdr));
         try!(writeln!(f, "ELF sections"));
-        for section in self.sections.values() {
+        for section in self.sections.iter() {
             try!(write!(f, "{:?}", section));
         }
         try!(writeln!(f, "ELF symbols"));
@@ -277,6 +682,58 @@ impl Object for File {
         }
     }
     fn get_section(&self, name: &str) -> Option<&Section> {
-        self.sections.get(name)
+        self.sections.iter().find(|x| x.name == name)
+    }
+    fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+    fn symbols(&self) -> &[::Symbol] {
+        &self.common_symbols
+    }
+    fn segments(&self) -> &[::Segment] {
+        &self.segments
+    }
+    fn entry(&self) -> Option<u64> {
+        match self.hdr.entrypoint {
+            0 => None,
+            x => Some(x),
+        }
+    }
+    fn image_base(&self) -> u64 {
+        // The first loadable segment, rounded down to its alignment
+        self.phdrs.iter()
+            .filter(|x| x.progtype == types::PT_LOAD)
+            .map(|x| if x.align > 1 { x.vaddr & !(x.align - 1) } else { x.vaddr })
+            .min()
+            .unwrap_or(0)
+    }
+    fn kind(&self) -> ::FileKind {
+        match self.hdr.elf_type {
+            types::ET_EXEC => ::FileKind::Executable,
+            types::ET_REL => ::FileKind::Relocatable,
+            types::ET_CORE => ::FileKind::Core,
+            // PIE executables are ET_DYN too; modern linkers mark them with
+            // DF_1_PIE
+            types::ET_DYN if self.dynamic.iter().any(|x| x.tag == types::DT_FLAGS_1 && x.val & types::DF_1_PIE != 0) => {
+                ::FileKind::Executable
+            }
+            types::ET_DYN => ::FileKind::SharedLibrary,
+            _ => ::FileKind::Unknown,
+        }
+    }
+    fn is_position_independent(&self) -> bool {
+        self.hdr.elf_type == types::ET_DYN
+    }
+    fn libraries(&self) -> &[String] {
+        &self.needed
+    }
+    fn imports(&self) -> &[::Import] {
+        &self.imports
+    }
+    fn exports(&self) -> &[::Export] {
+        &self.exports
+    }
+    fn relocations(&self) -> &[::Relocation] {
+        &self.relocations
     }
 }
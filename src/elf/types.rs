@@ -698,6 +698,7 @@ impl fmt::Display for FileHeader {
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct SectionHeader {
     pub name: String,
     pub shtype: SectionType,
@@ -734,3 +735,333 @@ pub struct ProgramHeader {
     pub flags: ProgFlag,
     pub align: u64,
 }
+
+/// Undefined section
+pub const SHN_UNDEF: u16 = 0;
+/// Absolute value, not relative to any section
+pub const SHN_ABS: u16 = 0xfff1;
+/// Common symbol
+pub const SHN_COMMON: u16 = 0xfff2;
+/// Section index is in the SHT_SYMTAB_SHNDX section
+pub const SHN_XINDEX: u16 = 0xffff;
+
+/// Local symbol
+pub const STB_LOCAL: u8 = 0;
+/// Global symbol
+pub const STB_GLOBAL: u8 = 1;
+/// Weak symbol
+pub const STB_WEAK: u8 = 2;
+/// Unique symbol (GNU extension)
+pub const STB_GNU_UNIQUE: u8 = 10;
+
+/// Unspecified type
+pub const STT_NOTYPE: u8 = 0;
+/// Data object
+pub const STT_OBJECT: u8 = 1;
+/// Code object
+pub const STT_FUNC: u8 = 2;
+/// Section
+pub const STT_SECTION: u8 = 3;
+/// Source file name
+pub const STT_FILE: u8 = 4;
+/// Common data object
+pub const STT_COMMON: u8 = 5;
+/// Thread-local data object
+pub const STT_TLS: u8 = 6;
+/// Indirect code object (GNU extension)
+pub const STT_GNU_IFUNC: u8 = 10;
+
+/// Default visibility
+pub const STV_DEFAULT: u8 = 0;
+/// Processor specific hidden class
+pub const STV_INTERNAL: u8 = 1;
+/// Not visible to other components
+pub const STV_HIDDEN: u8 = 2;
+/// Visible to other components but not preemptable
+pub const STV_PROTECTED: u8 = 3;
+
+/// Symbol table entry (Elf32_Sym / Elf64_Sym)
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub value: u64,
+    pub size: u64,
+    pub info: u8,
+    pub other: u8,
+    pub shndx: u16,
+}
+
+impl Symbol {
+    /// STB_* binding
+    pub fn bind(&self) -> u8 {
+        self.info >> 4
+    }
+    /// STT_* type
+    pub fn sym_type(&self) -> u8 {
+        self.info & 0xf
+    }
+    /// STV_* visibility
+    pub fn visibility(&self) -> u8 {
+        self.other & 0x3
+    }
+}
+
+/// End of the dynamic section
+pub const DT_NULL: u64 = 0;
+/// Name of a needed library
+pub const DT_NEEDED: u64 = 1;
+/// Address of the string table
+pub const DT_STRTAB: u64 = 5;
+/// Address of the symbol table
+pub const DT_SYMTAB: u64 = 6;
+/// Shared object name
+pub const DT_SONAME: u64 = 14;
+/// Library search path (deprecated)
+pub const DT_RPATH: u64 = 15;
+/// Library search path
+pub const DT_RUNPATH: u64 = 29;
+/// Flags for the object being loaded
+pub const DT_FLAGS: u64 = 30;
+/// State flags, see DF_1_*
+pub const DT_FLAGS_1: u64 = 0x6ffffffb;
+/// Address of the symbol version table
+pub const DT_VERSYM: u64 = 0x6ffffff0;
+/// Address of the version definition table
+pub const DT_VERDEF: u64 = 0x6ffffffc;
+/// Number of version definitions
+pub const DT_VERDEFNUM: u64 = 0x6ffffffd;
+/// Address of the version dependency table
+pub const DT_VERNEED: u64 = 0x6ffffffe;
+/// Number of version dependencies
+pub const DT_VERNEEDNUM: u64 = 0x6fffffff;
+
+/// DT_FLAGS_1: object is a position independent executable
+pub const DF_1_PIE: u64 = 0x08000000;
+
+/// Dynamic section entry
+#[derive(Debug)]
+pub struct Dynamic {
+    pub tag: u64,
+    pub val: u64,
+}
+
+/// Version index of a local symbol
+pub const VER_NDX_LOCAL: u16 = 0;
+/// Version index of an unversioned global symbol
+pub const VER_NDX_GLOBAL: u16 = 1;
+/// Version symbol bit marking a non-default version
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+/// Version definition of the file itself
+pub const VER_FLG_BASE: u16 = 0x1;
+/// Weak version reference
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+/// Symbol version defined by the object (.gnu.version_d)
+#[derive(Debug)]
+pub struct VersionDef {
+    pub index: u16,
+    pub flags: u16,
+    pub name: String,
+}
+
+/// Symbol version required from a library (.gnu.version_r)
+#[derive(Debug)]
+pub struct VersionNeed {
+    pub file: String,
+    pub index: u16,
+    pub flags: u16,
+    pub name: String,
+}
+
+pub const R_X86_64_NONE: u32 = 0;
+pub const R_X86_64_64: u32 = 1;
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_GOT32: u32 = 3;
+pub const R_X86_64_PLT32: u32 = 4;
+pub const R_X86_64_COPY: u32 = 5;
+pub const R_X86_64_GLOB_DAT: u32 = 6;
+pub const R_X86_64_JUMP_SLOT: u32 = 7;
+pub const R_X86_64_RELATIVE: u32 = 8;
+pub const R_X86_64_GOTPCREL: u32 = 9;
+pub const R_X86_64_32: u32 = 10;
+pub const R_X86_64_32S: u32 = 11;
+pub const R_X86_64_16: u32 = 12;
+pub const R_X86_64_PC16: u32 = 13;
+pub const R_X86_64_8: u32 = 14;
+pub const R_X86_64_PC8: u32 = 15;
+pub const R_X86_64_DTPMOD64: u32 = 16;
+pub const R_X86_64_DTPOFF64: u32 = 17;
+pub const R_X86_64_TPOFF64: u32 = 18;
+pub const R_X86_64_TLSGD: u32 = 19;
+pub const R_X86_64_TLSLD: u32 = 20;
+pub const R_X86_64_DTPOFF32: u32 = 21;
+pub const R_X86_64_GOTTPOFF: u32 = 22;
+pub const R_X86_64_TPOFF32: u32 = 23;
+pub const R_X86_64_PC64: u32 = 24;
+pub const R_X86_64_GOTOFF64: u32 = 25;
+pub const R_X86_64_GOTPC32: u32 = 26;
+pub const R_X86_64_GOTPC32_TLSDESC: u32 = 34;
+pub const R_X86_64_TLSDESC_CALL: u32 = 35;
+pub const R_X86_64_TLSDESC: u32 = 36;
+pub const R_X86_64_IRELATIVE: u32 = 37;
+pub const R_X86_64_GOTPCRELX: u32 = 41;
+pub const R_X86_64_REX_GOTPCRELX: u32 = 42;
+
+pub const R_386_NONE: u32 = 0;
+pub const R_386_32: u32 = 1;
+pub const R_386_PC32: u32 = 2;
+pub const R_386_GOT32: u32 = 3;
+pub const R_386_PLT32: u32 = 4;
+pub const R_386_COPY: u32 = 5;
+pub const R_386_GLOB_DAT: u32 = 6;
+pub const R_386_JMP_SLOT: u32 = 7;
+pub const R_386_RELATIVE: u32 = 8;
+pub const R_386_GOTOFF: u32 = 9;
+pub const R_386_GOTPC: u32 = 10;
+pub const R_386_TLS_TPOFF: u32 = 14;
+pub const R_386_TLS_LDM: u32 = 19;
+pub const R_386_16: u32 = 20;
+pub const R_386_PC16: u32 = 21;
+pub const R_386_8: u32 = 22;
+pub const R_386_PC8: u32 = 23;
+pub const R_386_TLS_GD_32: u32 = 24;
+pub const R_386_TLS_TPOFF32: u32 = 37;
+pub const R_386_TLS_GOTDESC: u32 = 39;
+pub const R_386_TLS_DESC: u32 = 41;
+pub const R_386_IRELATIVE: u32 = 42;
+pub const R_386_GOT32X: u32 = 43;
+
+pub const R_ARM_NONE: u32 = 0;
+pub const R_ARM_ABS32: u32 = 2;
+pub const R_ARM_REL32: u32 = 3;
+pub const R_ARM_ABS16: u32 = 5;
+pub const R_ARM_ABS12: u32 = 6;
+pub const R_ARM_ABS8: u32 = 8;
+pub const R_ARM_THM_CALL: u32 = 10;
+pub const R_ARM_TLS_DTPMOD32: u32 = 17;
+pub const R_ARM_TLS_DTPOFF32: u32 = 18;
+pub const R_ARM_TLS_TPOFF32: u32 = 19;
+pub const R_ARM_COPY: u32 = 20;
+pub const R_ARM_GLOB_DAT: u32 = 21;
+pub const R_ARM_JUMP_SLOT: u32 = 22;
+pub const R_ARM_RELATIVE: u32 = 23;
+pub const R_ARM_GOTOFF32: u32 = 24;
+pub const R_ARM_BASE_PREL: u32 = 25;
+pub const R_ARM_GOT_BREL: u32 = 26;
+pub const R_ARM_PLT32: u32 = 27;
+pub const R_ARM_CALL: u32 = 28;
+pub const R_ARM_JUMP24: u32 = 29;
+pub const R_ARM_THM_JUMP24: u32 = 30;
+pub const R_ARM_PREL31: u32 = 42;
+pub const R_ARM_MOVW_ABS_NC: u32 = 43;
+pub const R_ARM_MOVT_ABS: u32 = 44;
+pub const R_ARM_THM_MOVW_ABS_NC: u32 = 47;
+pub const R_ARM_THM_MOVT_ABS: u32 = 48;
+pub const R_ARM_GOT_PREL: u32 = 96;
+pub const R_ARM_TLS_GD32: u32 = 104;
+pub const R_ARM_TLS_LDM32: u32 = 105;
+pub const R_ARM_TLS_LDO32: u32 = 106;
+pub const R_ARM_TLS_IE32: u32 = 107;
+pub const R_ARM_TLS_LE32: u32 = 108;
+pub const R_ARM_IRELATIVE: u32 = 160;
+
+pub const R_AARCH64_NONE: u32 = 0;
+pub const R_AARCH64_ABS64: u32 = 257;
+pub const R_AARCH64_ABS32: u32 = 258;
+pub const R_AARCH64_ABS16: u32 = 259;
+pub const R_AARCH64_PREL64: u32 = 260;
+pub const R_AARCH64_PREL32: u32 = 261;
+pub const R_AARCH64_PREL16: u32 = 262;
+pub const R_AARCH64_MOVW_UABS_G0: u32 = 263;
+pub const R_AARCH64_MOVW_UABS_G3: u32 = 270;
+pub const R_AARCH64_LD_PREL_LO19: u32 = 273;
+pub const R_AARCH64_ADR_PREL_LO21: u32 = 274;
+pub const R_AARCH64_ADR_PREL_PG_HI21: u32 = 275;
+pub const R_AARCH64_ADR_PREL_PG_HI21_NC: u32 = 276;
+pub const R_AARCH64_ADD_ABS_LO12_NC: u32 = 277;
+pub const R_AARCH64_LDST8_ABS_LO12_NC: u32 = 278;
+pub const R_AARCH64_TSTBR14: u32 = 279;
+pub const R_AARCH64_CONDBR19: u32 = 280;
+pub const R_AARCH64_JUMP26: u32 = 282;
+pub const R_AARCH64_CALL26: u32 = 283;
+pub const R_AARCH64_LDST16_ABS_LO12_NC: u32 = 284;
+pub const R_AARCH64_LDST32_ABS_LO12_NC: u32 = 285;
+pub const R_AARCH64_LDST64_ABS_LO12_NC: u32 = 286;
+pub const R_AARCH64_LDST128_ABS_LO12_NC: u32 = 299;
+pub const R_AARCH64_ADR_GOT_PAGE: u32 = 311;
+pub const R_AARCH64_LD64_GOT_LO12_NC: u32 = 312;
+pub const R_AARCH64_LD64_GOTPAGE_LO15: u32 = 313;
+/// First of the static TLS relocation types
+pub const R_AARCH64_TLSGD_ADR_PREL21: u32 = 512;
+/// Last of the static TLS relocation types
+pub const R_AARCH64_TLSDESC_CALL: u32 = 569;
+pub const R_AARCH64_COPY: u32 = 1024;
+pub const R_AARCH64_GLOB_DAT: u32 = 1025;
+pub const R_AARCH64_JUMP_SLOT: u32 = 1026;
+pub const R_AARCH64_RELATIVE: u32 = 1027;
+pub const R_AARCH64_TLS_DTPMOD: u32 = 1028;
+pub const R_AARCH64_TLS_DTPREL: u32 = 1029;
+pub const R_AARCH64_TLS_TPREL: u32 = 1030;
+pub const R_AARCH64_TLSDESC: u32 = 1031;
+pub const R_AARCH64_IRELATIVE: u32 = 1032;
+
+/// Map a relocation type of the given machine to its portable category
+pub fn relocation_kind(machine: Machine, r_type: u32) -> ::RelocationKind {
+    match machine {
+        EM_X86_64 => match r_type {
+            R_X86_64_64 | R_X86_64_32 | R_X86_64_32S | R_X86_64_16 | R_X86_64_8 => ::RelocationKind::Absolute,
+            R_X86_64_PC32 | R_X86_64_PC16 | R_X86_64_PC8 | R_X86_64_PC64 => ::RelocationKind::PcRelative,
+            R_X86_64_GOT32 | R_X86_64_GLOB_DAT | R_X86_64_GOTPCREL | R_X86_64_GOTOFF64 | R_X86_64_GOTPC32 |
+            R_X86_64_GOTPCRELX | R_X86_64_REX_GOTPCRELX => ::RelocationKind::Got,
+            R_X86_64_PLT32 | R_X86_64_JUMP_SLOT => ::RelocationKind::Plt,
+            R_X86_64_DTPMOD64 ..= R_X86_64_TPOFF32 | R_X86_64_GOTPC32_TLSDESC ..= R_X86_64_TLSDESC => {
+                ::RelocationKind::Tls
+            }
+            R_X86_64_RELATIVE | R_X86_64_IRELATIVE => ::RelocationKind::BaseRelative,
+            _ => ::RelocationKind::Other,
+        },
+        EM_386 => match r_type {
+            R_386_32 | R_386_16 | R_386_8 => ::RelocationKind::Absolute,
+            R_386_PC32 | R_386_PC16 | R_386_PC8 => ::RelocationKind::PcRelative,
+            R_386_GOT32 | R_386_GLOB_DAT | R_386_GOTOFF | R_386_GOTPC | R_386_GOT32X => ::RelocationKind::Got,
+            R_386_PLT32 | R_386_JMP_SLOT => ::RelocationKind::Plt,
+            R_386_TLS_TPOFF ..= R_386_TLS_LDM | R_386_TLS_GD_32 ..= R_386_TLS_TPOFF32 |
+            R_386_TLS_GOTDESC ..= R_386_TLS_DESC => ::RelocationKind::Tls,
+            R_386_RELATIVE | R_386_IRELATIVE => ::RelocationKind::BaseRelative,
+            _ => ::RelocationKind::Other,
+        },
+        EM_ARM => match r_type {
+            R_ARM_ABS32 | R_ARM_ABS16 | R_ARM_ABS12 | R_ARM_ABS8 | R_ARM_MOVW_ABS_NC | R_ARM_MOVT_ABS |
+            R_ARM_THM_MOVW_ABS_NC | R_ARM_THM_MOVT_ABS => ::RelocationKind::Absolute,
+            R_ARM_REL32 | R_ARM_THM_CALL | R_ARM_CALL | R_ARM_JUMP24 | R_ARM_THM_JUMP24 | R_ARM_PREL31 => {
+                ::RelocationKind::PcRelative
+            }
+            R_ARM_GLOB_DAT | R_ARM_GOTOFF32 | R_ARM_BASE_PREL | R_ARM_GOT_BREL | R_ARM_GOT_PREL => ::RelocationKind::Got,
+            R_ARM_PLT32 | R_ARM_JUMP_SLOT => ::RelocationKind::Plt,
+            R_ARM_TLS_DTPMOD32 | R_ARM_TLS_DTPOFF32 | R_ARM_TLS_TPOFF32 | R_ARM_TLS_GD32 ..= R_ARM_TLS_LE32 => {
+                ::RelocationKind::Tls
+            }
+            R_ARM_RELATIVE | R_ARM_IRELATIVE => ::RelocationKind::BaseRelative,
+            _ => ::RelocationKind::Other,
+        },
+        EM_AARCH64 => match r_type {
+            R_AARCH64_ABS64 | R_AARCH64_ABS32 | R_AARCH64_ABS16 | R_AARCH64_MOVW_UABS_G0 ..= R_AARCH64_MOVW_UABS_G3 |
+            R_AARCH64_ADD_ABS_LO12_NC | R_AARCH64_LDST8_ABS_LO12_NC | R_AARCH64_LDST16_ABS_LO12_NC |
+            R_AARCH64_LDST32_ABS_LO12_NC | R_AARCH64_LDST64_ABS_LO12_NC | R_AARCH64_LDST128_ABS_LO12_NC => {
+                ::RelocationKind::Absolute
+            }
+            R_AARCH64_PREL64 | R_AARCH64_PREL32 | R_AARCH64_PREL16 | R_AARCH64_LD_PREL_LO19 ..= R_AARCH64_ADR_PREL_PG_HI21_NC |
+            R_AARCH64_TSTBR14 | R_AARCH64_CONDBR19 | R_AARCH64_JUMP26 | R_AARCH64_CALL26 => ::RelocationKind::PcRelative,
+            R_AARCH64_ADR_GOT_PAGE | R_AARCH64_LD64_GOT_LO12_NC | R_AARCH64_LD64_GOTPAGE_LO15 | R_AARCH64_GLOB_DAT => {
+                ::RelocationKind::Got
+            }
+            R_AARCH64_JUMP_SLOT => ::RelocationKind::Plt,
+            R_AARCH64_TLSGD_ADR_PREL21 ..= R_AARCH64_TLSDESC_CALL | R_AARCH64_TLS_DTPMOD ..= R_AARCH64_TLSDESC => {
+                ::RelocationKind::Tls
+            }
+            R_AARCH64_RELATIVE | R_AARCH64_IRELATIVE => ::RelocationKind::BaseRelative,
+            _ => ::RelocationKind::Other,
+        },
+        _ => ::RelocationKind::Other,
+    }
+}
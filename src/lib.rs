@@ -1,14 +1,19 @@
 extern crate byteorder;
 extern crate libc;
+extern crate sha1;
+extern crate sha2;
 
 pub mod pe;
 pub mod elf;
 pub mod mach;
+pub mod swift;
 
 use std::io;
 use std::error;
 use std::fmt;
 use std::default;
+use std::ops;
+use std::sync::Arc;
 
 pub struct Error {
     inner: Option<Box<error::Error>>,
@@ -44,6 +49,28 @@ impl fmt::Debug for Error {
     }
 }
 
+impl Error {
+    /// Error with `desc` as context for an underlying error
+    fn wrap(desc: &str, inner: Box<error::Error>) -> Error {
+        Error {
+            inner: Some(inner),
+            desc: String::from(desc),
+        }
+    }
+}
+
+/// Unwrap the result of decoding an optional part of the file, recording
+/// the error and falling back to an empty value if it failed
+fn non_fatal<T: Default>(errors: &mut Vec<Error>, what: &str, result: Result<T, Box<error::Error>>) -> T {
+    match result {
+        Ok(x) => x,
+        Err(e) => {
+            errors.push(Error::wrap(what, e));
+            T::default()
+        }
+    }
+}
+
 impl<'a> From<&'a str> for Error {
     fn from(s: &'a str) -> Error {
         Error {
@@ -53,7 +80,7 @@ impl<'a> From<&'a str> for Error {
     }
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum Arch {
     X86(Width),
     ARM(Width, Endianness, ARMMode, ARMType),
@@ -61,26 +88,26 @@ pub enum Arch {
     Unknown,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum Endianness {
     Little,
     Big,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum Width {
     W16,
     W32,
     W64,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum ARMMode {
     ARM,
     Thumb,
 }
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
 pub enum ARMType {
     ARM,
     MClass,
@@ -89,7 +116,113 @@ pub enum ARMType {
 
 pub trait Object {
     fn arch(&self) -> Arch;
+    /// First section named `name`
     fn get_section(&self, name: &str) -> Option<&Section>;
+    /// Sections in file order
+    fn sections(&self) -> &[Section];
+    /// Symbols of the object in a format independent representation
+    fn symbols(&self) -> &[Symbol];
+    /// Regions the loader maps into memory
+    fn segments(&self) -> &[Segment];
+    /// Virtual address of the entry point
+    fn entry(&self) -> Option<u64>;
+    /// Preferred load address of the image
+    fn image_base(&self) -> u64;
+    fn kind(&self) -> FileKind;
+    /// Whether the image can be loaded at an address other than its image base
+    fn is_position_independent(&self) -> bool;
+    /// Names of the libraries the image depends on, in load order
+    fn libraries(&self) -> &[String];
+    /// Symbols resolved from other images at load time
+    fn imports(&self) -> &[Import];
+    /// Symbols the image provides to other images
+    fn exports(&self) -> &[Export];
+    /// Relocations and loader fixups of the object
+    fn relocations(&self) -> &[Relocation];
+
+    /// Section at `index` in file order: the ELF section header index, the
+    /// PE section number minus one, or the Mach-O section ordinal minus one
+    fn section(&self, index: usize) -> Option<&Section> {
+        self.sections().get(index)
+    }
+    /// Every section named `name`, in file order. Names need not be unique:
+    /// COMDAT groups repeat ELF and COFF section names, and Mach-O section
+    /// names repeat across segments.
+    fn sections_by_name(&self, name: &str) -> Vec<&Section> {
+        self.sections().iter().filter(|x| x.name == name).collect()
+    }
+
+    /// Read `len` bytes at virtual address `addr`, crossing segment
+    /// boundaries as needed. Bytes past a segment's file contents (bss) read
+    /// as zero; addresses outside every readable segment, such as Mach-O's
+    /// `__PAGEZERO`, are an error.
+    fn read_virtual(&self, addr: u64, len: u64) -> Result<Vec<u8>, Box<error::Error>> {
+        let end = match addr.checked_add(len) {
+            Some(x) => x,
+            None => return Err(Box::new(Error::from("address range wraps around"))),
+        };
+        let mut ret = Vec::new();
+        let mut cur = addr;
+        while cur < end {
+            let seg = match self.segments().iter().find(|x| x.read && cur >= x.addr && cur - x.addr < x.size) {
+                Some(x) => x,
+                None => return Err(Box::new(Error::from("address not mapped by any readable segment"))),
+            };
+            let off = cur - seg.addr;
+            let n = (seg.size - off).min(end - cur);
+            if n > usize::MAX as u64 || ret.try_reserve(n as usize).is_err() {
+                return Err(Box::new(Error::from("address range too large to read")));
+            }
+            let data = seg.data.get(off as usize..).unwrap_or(&[]);
+            let copied = (n as usize).min(data.len());
+            ret.extend_from_slice(&data[..copied]);
+            ret.resize(ret.len() + (n as usize - copied), 0);
+            cur += n;
+        }
+        Ok(ret)
+    }
+}
+
+/// Part of the contents of a file. The sections and segments of a file
+/// share a single copy of it.
+#[derive(Clone, Default)]
+struct FileData {
+    buf: Arc<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl FileData {
+    /// `len` bytes at `offset` into `buf`, cut short at its end
+    fn new(buf: &Arc<Vec<u8>>, offset: u64, len: u64) -> FileData {
+        let size = buf.len() as u64;
+        FileData {
+            buf: buf.clone(),
+            start: offset.min(size) as usize,
+            end: offset.saturating_add(len).min(size) as usize,
+        }
+    }
+}
+
+/// Read the whole of `r` for the sections and segments to share
+fn read_file<R: io::Read + io::Seek>(r: &mut R) -> Result<Arc<Vec<u8>>, Box<error::Error>> {
+    let mut buf = Vec::new();
+    try!(r.seek(io::SeekFrom::Start(0)));
+    try!(r.read_to_end(&mut buf));
+    Ok(Arc::new(buf))
+}
+
+impl ops::Deref for FileData {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.buf[self.start..self.end]
+    }
+}
+
+impl fmt::Debug for FileData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
 }
 
 #[derive(Debug)]
@@ -98,7 +231,23 @@ pub struct Section {
     addr: u64,
     offset: u64,
     size: u64,
-    data: Vec<u8>,
+    data: FileData,
+    alloc: bool,
+    write: bool,
+    execute: bool,
+    bss: bool,
+    tls: bool,
+    debug: bool,
+    align: u64,
+    header: Option<SectionHeader>,
+}
+
+/// Format specific section header
+#[derive(Debug)]
+pub enum SectionHeader {
+    Elf(elf::types::SectionHeader),
+    Pe(pe::types::SectionHeader),
+    Mach(mach::types::SectionHeader),
 }
 
 impl Section {
@@ -117,6 +266,36 @@ impl Section {
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+    /// Occupies memory in the loaded image
+    pub fn is_alloc(&self) -> bool {
+        self.alloc
+    }
+    pub fn is_writable(&self) -> bool {
+        self.write
+    }
+    pub fn is_executable(&self) -> bool {
+        self.execute
+    }
+    /// Zero filled at load time, with no contents in the file
+    pub fn is_bss(&self) -> bool {
+        self.bss
+    }
+    /// Holds the initial image of thread-local variables
+    pub fn is_tls(&self) -> bool {
+        self.tls
+    }
+    /// Holds debugging information
+    pub fn is_debug(&self) -> bool {
+        self.debug
+    }
+    /// Required alignment of the section's address, 1 if unaligned
+    pub fn align(&self) -> u64 {
+        self.align
+    }
+    /// Header the section was parsed from; None only for `Section::default`
+    pub fn header(&self) -> Option<&SectionHeader> {
+        self.header.as_ref()
+    }
 }
 
 impl default::Default for Section {
@@ -126,11 +305,307 @@ impl default::Default for Section {
             addr: 0,
             offset: 0,
             size: u64::max_value(),
-            data: Vec::new(),
+            data: FileData::default(),
+            alloc: false,
+            write: false,
+            execute: false,
+            bss: false,
+            tls: false,
+            debug: false,
+            align: 1,
+            header: None,
         }
     }
 }
 
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Unknown,
+    Executable,
+    /// Shared library, or other dynamically loaded image such as a Mach-O
+    /// bundle
+    SharedLibrary,
+    /// Relocatable object file
+    Relocatable,
+    Core,
+}
+
+#[derive(Debug)]
+pub struct Segment {
+    name: Option<String>,
+    addr: u64,
+    size: u64,
+    offset: u64,
+    file_size: u64,
+    read: bool,
+    write: bool,
+    execute: bool,
+    data: FileData,
+}
+
+impl Segment {
+    /// Segment name for formats that have one (PE sections, Mach-O segments)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Virtual address
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+    /// Size in memory
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    /// File offset of the mapped contents
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    /// Number of bytes mapped from the file; the rest is zero filled
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+    pub fn is_readable(&self) -> bool {
+        self.read
+    }
+    pub fn is_writable(&self) -> bool {
+        self.write
+    }
+    pub fn is_executable(&self) -> bool {
+        self.execute
+    }
+    /// File contents of the segment
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Unknown,
+    /// Function or other code
+    Text,
+    /// Data object
+    Data,
+    Section,
+    /// Source file name
+    File,
+    /// Thread-local variable
+    Tls,
+    /// Uninitialized common block, allocated by the linker
+    Common,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum SymbolBinding {
+    Local,
+    Global,
+    Weak,
+}
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum SymbolVisibility {
+    Default,
+    /// Not visible outside the linked image (ELF STV_HIDDEN, Mach-O private
+    /// extern)
+    Hidden,
+    Protected,
+    Internal,
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    name: String,
+    address: u64,
+    size: u64,
+    kind: SymbolKind,
+    binding: SymbolBinding,
+    visibility: SymbolVisibility,
+    defined: bool,
+    section: Option<String>,
+}
+
+impl Symbol {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Virtual address, or the common block size for common symbols
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+    /// Size in bytes, 0 if the format does not record it
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    pub fn kind(&self) -> SymbolKind {
+        self.kind
+    }
+    pub fn binding(&self) -> SymbolBinding {
+        self.binding
+    }
+    pub fn visibility(&self) -> SymbolVisibility {
+        self.visibility
+    }
+    pub fn is_defined(&self) -> bool {
+        self.defined
+    }
+    pub fn is_undefined(&self) -> bool {
+        !self.defined
+    }
+    /// Name of the section defining the symbol
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+}
+
+/// Symbol imported from another image
+#[derive(Debug)]
+pub struct Import {
+    library: Option<String>,
+    name: Option<String>,
+    ordinal: Option<u32>,
+    version: Option<String>,
+    weak: bool,
+    delay: bool,
+    address: Option<u64>,
+}
+
+impl Import {
+    /// Library providing the symbol, when the format records it
+    pub fn library(&self) -> Option<&str> {
+        self.library.as_deref()
+    }
+    /// Symbol name; None for imports by ordinal
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Export ordinal of a PE import by ordinal
+    pub fn ordinal(&self) -> Option<u32> {
+        self.ordinal
+    }
+    /// Required symbol version (ELF symbol versioning)
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+    /// The image still loads if the symbol cannot be resolved
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+    /// Resolved on first use rather than at load time (PE delay imports,
+    /// Mach-O lazily loaded dylibs)
+    pub fn is_delayed(&self) -> bool {
+        self.delay
+    }
+    /// Virtual address of the pointer the loader writes the resolved address
+    /// to, when there is a single such location
+    pub fn address(&self) -> Option<u64> {
+        self.address
+    }
+}
+
+/// Symbol exported to other images
+#[derive(Debug)]
+pub struct Export {
+    name: Option<String>,
+    ordinal: Option<u32>,
+    address: u64,
+    version: Option<String>,
+    weak: bool,
+    forwarder: Option<String>,
+}
+
+impl Export {
+    /// Symbol name; None for PE exports by ordinal only
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// PE export ordinal
+    pub fn ordinal(&self) -> Option<u32> {
+        self.ordinal
+    }
+    /// Virtual address of the symbol, 0 for forwarded exports
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+    /// Symbol version (ELF symbol versioning)
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+    /// Weak definition that another image may override
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+    /// Where a forwarded export (PE forwarder, Mach-O re-export) actually
+    /// lives, as `library.symbol`
+    pub fn forwarder(&self) -> Option<&str> {
+        self.forwarder.as_deref()
+    }
+}
+
+/// Format independent relocation category
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum RelocationKind {
+    /// Symbol address plus addend
+    Absolute,
+    /// Symbol address plus addend, relative to the relocated location
+    PcRelative,
+    /// Refers to the global offset table or the symbol's entry in it
+    Got,
+    /// Refers to the symbol's procedure linkage table entry, or fills a
+    /// lazily bound pointer
+    Plt,
+    /// Thread-local storage offset, module index or descriptor
+    Tls,
+    /// Adjusted by the difference between the load address and the image
+    /// base (ELF RELATIVE, PE base relocations, Mach-O rebases)
+    BaseRelative,
+    /// Symbol address relative to the image base (PE RVAs)
+    ImageRelative,
+    Other,
+}
+
+#[derive(Debug)]
+pub struct Relocation {
+    address: u64,
+    section: Option<String>,
+    kind: RelocationKind,
+    raw_type: u32,
+    symbol: Option<String>,
+    addend: i64,
+}
+
+impl Relocation {
+    /// Virtual address of the relocated location; for relocatable files the
+    /// section address plus the offset into the section
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+    /// Name of the section containing the relocated location
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+    pub fn kind(&self) -> RelocationKind {
+        self.kind
+    }
+    /// Format and architecture specific relocation type
+    pub fn raw_type(&self) -> u32 {
+        self.raw_type
+    }
+    /// Name of the target symbol, or of the target section for section
+    /// relative relocations
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+    /// Explicit addend. ELF REL, COFF and most Mach-O relocations keep the
+    /// addend in the relocated location instead.
+    pub fn addend(&self) -> i64 {
+        self.addend
+    }
+}
+
+/// Parse an executable, trying each supported format in turn.
+///
+/// For a universal Mach-O binary the first slice that parses is returned;
+/// use `parse_all` or `parse_arch` to get at the others.
 pub fn parse<R: io::Read + io::Seek>(r: &mut R) -> Result<Box<Object>, Box<error::Error>> {
     if let Ok(x) = elf::File::parse(r) {
         Ok(Box::new(x))
@@ -138,7 +613,44 @@ pub fn parse<R: io::Read + io::Seek>(r: &mut R) -> Result<Box<Object>, Box<error
         Ok(Box::new(x))
     } else if let Ok(x) = mach::File::parse(r) {
         Ok(Box::new(x))
+    } else if let Ok(x) = mach::FatFile::parse(r) {
+        // The first slice that parses, or else the first slice's error
+        let mut slices = x.slices(r);
+        let i = slices.iter().position(|x| x.is_ok()).unwrap_or(0);
+        Ok(Box::new(try!(slices.swap_remove(i))))
     } else {
         Err(Box::new(Error::from("Invalid format")))
     }
 }
+
+/// Outcome of parsing one object of an executable
+pub type ParseResult = Result<Box<Object>, Box<error::Error>>;
+
+/// Parse every object contained in an executable: each slice of a universal
+/// Mach-O binary, or the single object of any other format. Slices are
+/// parsed independently, so a corrupt slice only fails its own entry.
+pub fn parse_all<R: io::Read + io::Seek>(r: &mut R) -> Result<Vec<ParseResult>, Box<error::Error>> {
+    if let Ok(x) = mach::FatFile::parse(r) {
+        Ok(x.slices(r).into_iter().map(|x| x.map(|y| Box::new(y) as Box<Object>)).collect())
+    } else {
+        Ok(vec![Ok(try!(parse(r)))])
+    }
+}
+
+/// Parse the object for `arch` from an executable, selecting the matching
+/// slice of a universal Mach-O binary. Only the selected slice is parsed.
+pub fn parse_arch<R: io::Read + io::Seek>(r: &mut R, arch: Arch) -> Result<Box<Object>, Box<error::Error>> {
+    if let Ok(x) = mach::FatFile::parse(r) {
+        match x.archs().iter().position(|y| y.arch() == arch) {
+            Some(i) => Ok(Box::new(try!(x.slice(r, i)))),
+            None => Err(Box::new(Error::from("Architecture not found"))),
+        }
+    } else {
+        let obj = try!(parse(r));
+        if obj.arch() == arch {
+            Ok(obj)
+        } else {
+            Err(Box::new(Error::from("Architecture not found")))
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::io::prelude::*;
+use std::io;
+use std::error;
+use byteorder;
+use byteorder::ReadBytesExt;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384};
+use mach::types;
+use Error;
+
+// Code signature structures are always big endian, regardless of the byte
+// order of the Mach-O file they are embedded in.
+
+/// Hash `data` with a CS_HASHTYPE_* algorithm, or `None` if it is unknown
+pub fn hash(hash_type: u8, data: &[u8]) -> Option<Vec<u8>> {
+    match hash_type {
+        types::CS_HASHTYPE_SHA1 => Some(Sha1::digest(data).to_vec()),
+        types::CS_HASHTYPE_SHA256 => Some(Sha256::digest(data).to_vec()),
+        types::CS_HASHTYPE_SHA256_TRUNCATED => Some(Sha256::digest(data)[..20].to_vec()),
+        types::CS_HASHTYPE_SHA384 => Some(Sha384::digest(data).to_vec()),
+        _ => None,
+    }
+}
+
+fn get_string(data: &[u8], start: usize) -> Result<String, Box<error::Error>> {
+    let tail = match data.get(start..) {
+        Some(x) => x,
+        None => return Err(Box::new(Error::from("invalid code directory string offset"))),
+    };
+    let end = tail.iter().position(|x| *x == 0).unwrap_or(tail.len());
+    Ok(String::from_utf8_lossy(&tail[..end]).into_owned())
+}
+
+fn parse_code_directory(blob: &[u8]) -> Result<types::CodeDirectory, Box<error::Error>> {
+    let mut c = io::Cursor::new(blob);
+    let _magic = try!(c.read_u32::<byteorder::BigEndian>());
+    let _length = try!(c.read_u32::<byteorder::BigEndian>());
+    let version = try!(c.read_u32::<byteorder::BigEndian>());
+    let flags = try!(c.read_u32::<byteorder::BigEndian>());
+    let hash_offset = try!(c.read_u32::<byteorder::BigEndian>());
+    let ident_offset = try!(c.read_u32::<byteorder::BigEndian>());
+    let n_special_slots = try!(c.read_u32::<byteorder::BigEndian>());
+    let n_code_slots = try!(c.read_u32::<byteorder::BigEndian>());
+    let code_limit = try!(c.read_u32::<byteorder::BigEndian>());
+    let hash_size = try!(c.read_u8());
+    let hash_type = try!(c.read_u8());
+    let platform = try!(c.read_u8());
+    let page_size = try!(c.read_u8());
+    let _spare2 = try!(c.read_u32::<byteorder::BigEndian>());
+
+    let mut team_offset = 0;
+    let mut code_limit64 = 0;
+    let mut exec_seg = None;
+    let mut runtime = None;
+    if version >= types::CS_SUPPORTSSCATTER {
+        let _scatter_offset = try!(c.read_u32::<byteorder::BigEndian>());
+    }
+    if version >= types::CS_SUPPORTSTEAMID {
+        team_offset = try!(c.read_u32::<byteorder::BigEndian>());
+    }
+    if version >= types::CS_SUPPORTSCODELIMIT64 {
+        let _spare3 = try!(c.read_u32::<byteorder::BigEndian>());
+        code_limit64 = try!(c.read_u64::<byteorder::BigEndian>());
+    }
+    if version >= types::CS_SUPPORTSEXECSEG {
+        let base = try!(c.read_u64::<byteorder::BigEndian>());
+        let limit = try!(c.read_u64::<byteorder::BigEndian>());
+        let flags = try!(c.read_u64::<byteorder::BigEndian>());
+        exec_seg = Some((base, limit, flags));
+    }
+    if version >= types::CS_SUPPORTSRUNTIME {
+        runtime = Some(types::Version(try!(c.read_u32::<byteorder::BigEndian>())));
+    }
+
+    if hash_size == 0 {
+        try!(Err(Error::from("invalid code directory hash size")));
+    }
+    if page_size > 63 {
+        try!(Err(Error::from("invalid code directory page size")));
+    }
+    // The slot counts are not to be trusted before the hashes are known to
+    // fit in the blob
+    if (n_special_slots as u64 + n_code_slots as u64) * hash_size as u64 > blob.len() as u64 {
+        try!(Err(Error::from("truncated code directory hash slots")));
+    }
+
+    let hash_size_u = hash_size as usize;
+    let slot = |index: i64| -> Result<Vec<u8>, Box<error::Error>> {
+        let start = hash_offset as i64 + index * hash_size_u as i64;
+        if start < 0 {
+            return Err(Box::new(Error::from("invalid code directory hash offset")));
+        }
+        match (start as usize).checked_add(hash_size_u).and_then(|end| blob.get(start as usize..end)) {
+            Some(x) => Ok(x.to_vec()),
+            None => Err(Box::new(Error::from("truncated code directory hash slots"))),
+        }
+    };
+    // Special slot N is stored N hashes before the code slots
+    let mut special_slots = Vec::new();
+    for i in 1..(n_special_slots as i64 + 1) {
+        special_slots.push(try!(slot(-i)));
+    }
+    let mut code_slots = Vec::new();
+    for i in 0..n_code_slots as i64 {
+        code_slots.push(try!(slot(i)));
+    }
+
+    Ok(types::CodeDirectory {
+        version: version,
+        flags: flags,
+        hash_type: hash_type,
+        hash_size: hash_size,
+        platform: platform,
+        page_size: if page_size == 0 { 0 } else { 1u64 << page_size },
+        identifier: try!(get_string(blob, ident_offset as usize)),
+        team_id: if team_offset != 0 {
+            Some(try!(get_string(blob, team_offset as usize)))
+        } else {
+            None
+        },
+        code_limit: if code_limit64 != 0 { code_limit64 } else { code_limit as u64 },
+        exec_seg_base: exec_seg.map(|x| x.0),
+        exec_seg_limit: exec_seg.map(|x| x.1),
+        exec_seg_flags: exec_seg.map(|x| x.2),
+        runtime: runtime,
+        special_slots: special_slots,
+        code_slots: code_slots,
+        cdhash: hash(hash_type, blob).map(|mut x| {
+            x.truncate(20);
+            x
+        }),
+        raw: blob.to_vec(),
+    })
+}
+
+/// Parse the embedded signature SuperBlob found at LC_CODE_SIGNATURE
+pub fn parse_code_signature(data: &[u8]) -> Result<types::CodeSignature, Box<error::Error>> {
+    let mut c = io::Cursor::new(data);
+    let magic = try!(c.read_u32::<byteorder::BigEndian>());
+    if magic != types::CSMAGIC_EMBEDDED_SIGNATURE {
+        try!(Err(Error::from("invalid code signature magic")));
+    }
+    let _length = try!(c.read_u32::<byteorder::BigEndian>());
+    let count = try!(c.read_u32::<byteorder::BigEndian>());
+    let mut index = Vec::new();
+    for _ in 0..count {
+        let slot = try!(c.read_u32::<byteorder::BigEndian>());
+        let offset = try!(c.read_u32::<byteorder::BigEndian>());
+        index.push((slot, offset));
+    }
+
+    let mut ret = types::CodeSignature {
+        blobs: Vec::new(),
+        code_directories: Vec::new(),
+        requirements: None,
+        entitlements: None,
+        der_entitlements: None,
+        cms: None,
+    };
+    for (slot, offset) in index.into_iter() {
+        let mut c = io::Cursor::new(data);
+        try!(c.seek(io::SeekFrom::Start(offset as u64)));
+        let magic = try!(c.read_u32::<byteorder::BigEndian>());
+        let length = try!(c.read_u32::<byteorder::BigEndian>()) as usize;
+        let blob = match data.get(offset as usize..offset as usize + length) {
+            Some(x) if length >= 8 => x,
+            _ => return Err(Box::new(Error::from("truncated code signature blob"))),
+        };
+        match magic {
+            types::CSMAGIC_CODEDIRECTORY => ret.code_directories.push(try!(parse_code_directory(blob))),
+            types::CSMAGIC_REQUIREMENTS => ret.requirements = Some(blob.to_vec()),
+            types::CSMAGIC_EMBEDDED_ENTITLEMENTS => {
+                ret.entitlements = Some(String::from_utf8_lossy(&blob[8..]).into_owned());
+            }
+            types::CSMAGIC_EMBEDDED_DER_ENTITLEMENTS => ret.der_entitlements = Some(blob[8..].to_vec()),
+            types::CSMAGIC_BLOBWRAPPER if slot == types::CSSLOT_SIGNATURESLOT => ret.cms = Some(blob[8..].to_vec()),
+            _ => {}
+        }
+        ret.blobs.push(types::CodeSignatureBlob {
+            slot: slot,
+            magic: magic,
+            data: blob.to_vec(),
+        });
+    }
+    Ok(ret)
+}
+
+/// Check the page and special slot hashes of `cd` against the file in `r`
+pub fn verify<R: io::Read + io::Seek>(r: &mut R, sig: &types::CodeSignature, cd: &types::CodeDirectory)
+                                      -> Result<types::SignatureVerification, Box<error::Error>> {
+    let mut ret = types::SignatureVerification {
+        hash_type: cd.hash_type,
+        bad_pages: Vec::new(),
+        bad_special_slots: Vec::new(),
+    };
+    if hash(cd.hash_type, &[]).is_none() {
+        try!(Err(Error::from("unsupported code directory hash type")));
+    }
+    let hash_size = cd.hash_size as usize;
+
+    let page_size = if cd.page_size == 0 { cd.code_limit } else { cd.page_size };
+    for (i, expected) in cd.code_slots.iter().enumerate() {
+        let start = i as u64 * page_size;
+        let len = page_size.min(cd.code_limit.saturating_sub(start));
+        try!(r.seek(io::SeekFrom::Start(start)));
+        let mut page = Vec::new();
+        try!(io::Read::by_ref(r).take(len).read_to_end(&mut page));
+        let actual = hash(cd.hash_type, &page).unwrap();
+        if page.len() as u64 != len || actual.get(..hash_size) != Some(expected.as_slice()) {
+            ret.bad_pages.push(i);
+        }
+    }
+
+    // Special slots are only checked for blobs that live in the signature
+    // itself; the Info.plist and resource directory are outside the binary
+    for (i, expected) in cd.special_slots.iter().enumerate() {
+        let slot = i as u32 + 1;
+        if expected.iter().all(|x| *x == 0) {
+            continue;
+        }
+        let blob = match sig.blobs.iter().find(|x| x.slot == slot) {
+            Some(x) => x,
+            None => continue,
+        };
+        let actual = hash(cd.hash_type, &blob.data).unwrap();
+        if actual.get(..hash_size) != Some(expected.as_slice()) {
+            ret.bad_special_slots.push(slot as usize);
+        }
+    }
+    Ok(ret)
+}
@@ -0,0 +1,359 @@
+use std::error;
+use mach::types;
+use Error;
+
+/// Read an unsigned LEB128 value at `*pos`, advancing it
+pub fn read_uleb128(data: &[u8], pos: &mut usize) -> Result<u64, Box<error::Error>> {
+    let mut ret = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = match data.get(*pos) {
+            Some(x) => *x,
+            None => return Err(Box::new(Error::from("truncated uleb128"))),
+        };
+        *pos += 1;
+        if shift >= 64 {
+            return Err(Box::new(Error::from("uleb128 too big")));
+        }
+        ret |= ((byte & 0x7f) as u64) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Ok(ret);
+        }
+    }
+}
+
+/// Read a signed LEB128 value at `*pos`, advancing it
+pub fn read_sleb128(data: &[u8], pos: &mut usize) -> Result<i64, Box<error::Error>> {
+    let mut ret = 0i64;
+    let mut shift = 0;
+    loop {
+        let byte = match data.get(*pos) {
+            Some(x) => *x,
+            None => return Err(Box::new(Error::from("truncated sleb128"))),
+        };
+        *pos += 1;
+        if shift >= 64 {
+            return Err(Box::new(Error::from("sleb128 too big")));
+        }
+        ret |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && byte & 0x40 != 0 {
+                ret |= -1i64 << shift;
+            }
+            return Ok(ret);
+        }
+    }
+}
+
+/// Read a nul-terminated string at `*pos`, advancing past the terminator
+pub fn read_cstr(data: &[u8], pos: &mut usize) -> Result<String, Box<error::Error>> {
+    let start = *pos;
+    match data[start.min(data.len())..].iter().position(|x| *x == 0) {
+        Some(len) => {
+            *pos = start + len + 1;
+            Ok(String::from_utf8_lossy(&data[start..start + len]).into_owned())
+        }
+        None => Err(Box::new(Error::from("unterminated string"))),
+    }
+}
+
+/// Decode the ULEB128 delta list of LC_FUNCTION_STARTS. The first delta is
+/// relative to `base`, the start of __TEXT; a zero delta ends the list.
+pub fn parse_function_starts(data: &[u8], base: u64) -> Result<Vec<u64>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    let mut pos = 0;
+    let mut addr = base;
+    while pos < data.len() {
+        let delta = try!(read_uleb128(data, &mut pos));
+        if delta == 0 {
+            break;
+        }
+        addr = addr.wrapping_add(delta);
+        ret.push(addr);
+    }
+    Ok(ret)
+}
+
+fn seg_addr(segments: &[types::SegmentCommand], seg_index: u8, seg_offset: u64) -> Result<u64, Box<error::Error>> {
+    match segments.get(seg_index as usize) {
+        Some(seg) if seg_offset < seg.vm_size => Ok(seg.vm_addr.wrapping_add(seg_offset)),
+        Some(_) => Err(Box::new(Error::from("segment offset out of range"))),
+        None => Err(Box::new(Error::from("invalid segment index"))),
+    }
+}
+
+/// Check a ULEB128 repeat count against the pointers left in the segment,
+/// so a corrupt count fails instead of running away
+fn repeat_count(segments: &[types::SegmentCommand], seg_index: u8, seg_offset: u64, count: u64, ptr_size: u64)
+                -> Result<u64, Box<error::Error>> {
+    let left = match segments.get(seg_index as usize) {
+        Some(seg) => seg.vm_size.saturating_sub(seg_offset),
+        None => return Err(Box::new(Error::from("invalid segment index"))),
+    };
+    if count > left / ptr_size {
+        return Err(Box::new(Error::from("repeat count out of range")));
+    }
+    Ok(count)
+}
+
+/// Interpret a rebase opcode stream
+pub fn parse_rebases(data: &[u8], segments: &[types::SegmentCommand], ptr_size: u64)
+                     -> Result<Vec<types::Rebase>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    let mut pos = 0;
+    let mut rebase_type = 0u8;
+    let mut seg_index = 0u8;
+    let mut seg_offset = 0u64;
+
+    macro_rules! do_rebase {
+        () => {
+            ret.push(types::Rebase {
+                seg_index: seg_index,
+                seg_offset: seg_offset,
+                address: try!(seg_addr(segments, seg_index, seg_offset)),
+                rebase_type: rebase_type,
+            });
+        }
+    }
+
+    while pos < data.len() {
+        let byte = data[pos];
+        pos += 1;
+        let imm = byte & types::REBASE_IMMEDIATE_MASK;
+        match byte & types::REBASE_OPCODE_MASK {
+            types::REBASE_OPCODE_DONE => break,
+            types::REBASE_OPCODE_SET_TYPE_IMM => rebase_type = imm,
+            types::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                seg_index = imm;
+                seg_offset = try!(read_uleb128(data, &mut pos));
+            }
+            types::REBASE_OPCODE_ADD_ADDR_ULEB => {
+                seg_offset = seg_offset.wrapping_add(try!(read_uleb128(data, &mut pos)));
+            }
+            types::REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                seg_offset = seg_offset.wrapping_add(imm as u64 * ptr_size);
+            }
+            types::REBASE_OPCODE_DO_REBASE_IMM_TIMES => {
+                for _ in 0..imm {
+                    do_rebase!();
+                    seg_offset = seg_offset.wrapping_add(ptr_size);
+                }
+            }
+            types::REBASE_OPCODE_DO_REBASE_ULEB_TIMES => {
+                let count = try!(read_uleb128(data, &mut pos));
+                let count = try!(repeat_count(segments, seg_index, seg_offset, count, ptr_size));
+                for _ in 0..count {
+                    do_rebase!();
+                    seg_offset = seg_offset.wrapping_add(ptr_size);
+                }
+            }
+            types::REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => {
+                do_rebase!();
+                seg_offset = seg_offset.wrapping_add(try!(read_uleb128(data, &mut pos))).wrapping_add(ptr_size);
+            }
+            types::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = try!(read_uleb128(data, &mut pos));
+                let count = try!(repeat_count(segments, seg_index, seg_offset, count, ptr_size));
+                let skip = try!(read_uleb128(data, &mut pos));
+                for _ in 0..count {
+                    do_rebase!();
+                    seg_offset = seg_offset.wrapping_add(skip).wrapping_add(ptr_size);
+                }
+            }
+            _ => return Err(Box::new(Error::from("invalid rebase opcode"))),
+        }
+    }
+    Ok(ret)
+}
+
+/// Interpret a bind, weak bind or lazy bind opcode stream.
+///
+/// Library ordinals are resolved against `dylibs`, the dependent libraries
+/// in load command order.
+pub fn parse_binds(data: &[u8], kind: types::BindKind, segments: &[types::SegmentCommand],
+                   dylibs: &[types::Dylib], ptr_size: u64)
+                   -> Result<Vec<types::Bind>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    let mut pos = 0;
+    let mut bind_type = types::BIND_TYPE_POINTER;
+    let mut seg_index = 0u8;
+    let mut seg_offset = 0u64;
+    let mut ordinal = 0i64;
+    let mut symbol_name = String::new();
+    let mut symbol_flags = 0u8;
+    let mut addend = 0i64;
+
+    macro_rules! do_bind {
+        () => {
+            ret.push(types::Bind {
+                kind: kind,
+                seg_index: seg_index,
+                seg_offset: seg_offset,
+                address: try!(seg_addr(segments, seg_index, seg_offset)),
+                bind_type: bind_type,
+                symbol_name: symbol_name.clone(),
+                symbol_flags: symbol_flags,
+                library_ordinal: ordinal,
+                library: if ordinal > 0 {
+                    dylibs.get(ordinal as usize - 1).map(|x| x.name.clone())
+                } else {
+                    None
+                },
+                addend: addend,
+            });
+        }
+    }
+
+    while pos < data.len() {
+        let byte = data[pos];
+        pos += 1;
+        let imm = byte & types::BIND_IMMEDIATE_MASK;
+        match byte & types::BIND_OPCODE_MASK {
+            types::BIND_OPCODE_DONE => {
+                // Lazy binding info is a sequence of independent entries, each
+                // terminated by BIND_OPCODE_DONE
+                if kind != types::BindKind::Lazy {
+                    break;
+                }
+            }
+            types::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM => ordinal = imm as i64,
+            types::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB => ordinal = try!(read_uleb128(data, &mut pos)) as i64,
+            types::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM => {
+                // Special ordinals are zero or small negative numbers
+                ordinal = if imm == 0 {
+                    0
+                } else {
+                    (types::BIND_OPCODE_MASK | imm) as i8 as i64
+                };
+            }
+            types::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM => {
+                symbol_flags = imm;
+                symbol_name = try!(read_cstr(data, &mut pos));
+            }
+            types::BIND_OPCODE_SET_TYPE_IMM => bind_type = imm,
+            types::BIND_OPCODE_SET_ADDEND_SLEB => addend = try!(read_sleb128(data, &mut pos)),
+            types::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                seg_index = imm;
+                seg_offset = try!(read_uleb128(data, &mut pos));
+            }
+            types::BIND_OPCODE_ADD_ADDR_ULEB => {
+                seg_offset = seg_offset.wrapping_add(try!(read_uleb128(data, &mut pos)));
+            }
+            types::BIND_OPCODE_DO_BIND => {
+                do_bind!();
+                seg_offset = seg_offset.wrapping_add(ptr_size);
+            }
+            types::BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB => {
+                do_bind!();
+                seg_offset = seg_offset.wrapping_add(try!(read_uleb128(data, &mut pos))).wrapping_add(ptr_size);
+            }
+            types::BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED => {
+                do_bind!();
+                seg_offset = seg_offset.wrapping_add(imm as u64 * ptr_size).wrapping_add(ptr_size);
+            }
+            types::BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB => {
+                let count = try!(read_uleb128(data, &mut pos));
+                let count = try!(repeat_count(segments, seg_index, seg_offset, count, ptr_size));
+                let skip = try!(read_uleb128(data, &mut pos));
+                for _ in 0..count {
+                    do_bind!();
+                    seg_offset = seg_offset.wrapping_add(skip).wrapping_add(ptr_size);
+                }
+            }
+            types::BIND_OPCODE_THREADED => {
+                // Threaded (pre-chained-fixup arm64e) binds encode their
+                // locations in the pointers themselves and are not listed here
+                if imm == types::BIND_SUBOPCODE_THREADED_SET_BIND_ORDINAL_TABLE_SIZE_ULEB {
+                    try!(read_uleb128(data, &mut pos));
+                }
+            }
+            _ => return Err(Box::new(Error::from("invalid bind opcode"))),
+        }
+    }
+    Ok(ret)
+}
+
+/// Decode an export trie into its exported symbols.
+///
+/// `base` is the address of the mach header, which non-absolute export
+/// values are relative to; re-export ordinals are resolved against `dylibs`.
+pub fn parse_export_trie(data: &[u8], base: u64, dylibs: &[types::Dylib])
+                         -> Result<Vec<types::Export>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if data.is_empty() {
+        return Ok(ret);
+    }
+    // Each node is visited once; a node reached twice means the trie is
+    // malformed and would otherwise loop forever
+    let mut visited = vec![false; data.len()];
+    let mut stack = vec![(0usize, String::new())];
+    while let Some((node, prefix)) = stack.pop() {
+        if node >= data.len() || visited[node] {
+            return Err(Box::new(Error::from("invalid export trie node")));
+        }
+        visited[node] = true;
+
+        let mut pos = node;
+        let terminal_size = try!(read_uleb128(data, &mut pos)) as usize;
+        let children = match pos.checked_add(terminal_size) {
+            Some(x) if x <= data.len() => x,
+            _ => return Err(Box::new(Error::from("invalid export trie node"))),
+        };
+        if terminal_size != 0 {
+            let flags = try!(read_uleb128(data, &mut pos));
+            let mut value = 0;
+            let mut resolver = None;
+            let mut reexport = None;
+            if flags & types::EXPORT_SYMBOL_FLAGS_REEXPORT != 0 {
+                let ordinal = try!(read_uleb128(data, &mut pos));
+                let mut name = try!(read_cstr(data, &mut pos));
+                if name.is_empty() {
+                    name = prefix.clone();
+                }
+                reexport = Some(types::ReExport {
+                    library_ordinal: ordinal,
+                    library: if ordinal > 0 {
+                        dylibs.get(ordinal as usize - 1).map(|x| x.name.clone())
+                    } else {
+                        None
+                    },
+                    name: name,
+                });
+            } else {
+                value = try!(read_uleb128(data, &mut pos));
+                if flags & types::EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0 {
+                    resolver = Some(base.wrapping_add(try!(read_uleb128(data, &mut pos))));
+                }
+            }
+            let address = match flags & types::EXPORT_SYMBOL_FLAGS_KIND_MASK {
+                types::EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE => value,
+                _ if reexport.is_some() => 0,
+                _ => base.wrapping_add(value),
+            };
+            ret.push(types::Export {
+                name: prefix.clone(),
+                flags: flags,
+                value: value,
+                address: address,
+                resolver: resolver,
+                reexport: reexport,
+            });
+        }
+
+        pos = children;
+        let child_count = match data.get(pos) {
+            Some(x) => *x,
+            None => return Err(Box::new(Error::from("truncated export trie"))),
+        };
+        pos += 1;
+        for _ in 0..child_count {
+            let edge = try!(read_cstr(data, &mut pos));
+            let child = try!(read_uleb128(data, &mut pos)) as usize;
+            stack.push((child, prefix.clone() + &edge));
+        }
+    }
+    ret.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(ret)
+}
@@ -0,0 +1,92 @@
+use std::io::prelude::*;
+use std::io;
+use std::error;
+use byteorder;
+use byteorder::ReadBytesExt;
+use mach::types;
+use mach::File;
+use Error;
+
+/// Upper bound on slice count, used to tell universal binaries apart from
+/// Java class files, which share the 0xCAFEBABE magic
+const MAX_FAT_ARCHS: u32 = 0x20;
+
+pub struct FatFile {
+    archs: Vec<types::FatArch>,
+}
+
+impl FatFile {
+    pub fn parse<R: io::Read + io::Seek>(r: &mut R) -> Result<FatFile, Box<error::Error>> {
+        try!(r.seek(io::SeekFrom::Start(0)));
+        // Universal headers are always big endian
+        let magic = try!(r.read_u32::<byteorder::BigEndian>());
+        if magic != types::FAT_MAGIC && magic != types::FAT_MAGIC_64 {
+            try!(Err(Error::from("invalid magic number")));
+        }
+
+        let nfat_arch = try!(r.read_u32::<byteorder::BigEndian>());
+        if nfat_arch == 0 || nfat_arch > MAX_FAT_ARCHS {
+            try!(Err(Error::from("invalid number of architectures")));
+        }
+
+        let mut archs = Vec::new();
+        for _ in 0..nfat_arch {
+            let cpu_type = types::Machine(try!(r.read_i32::<byteorder::BigEndian>()));
+            let cpu_subtype = try!(r.read_u32::<byteorder::BigEndian>());
+            let offset: u64;
+            let size: u64;
+            if magic == types::FAT_MAGIC_64 {
+                offset = try!(r.read_u64::<byteorder::BigEndian>());
+                size = try!(r.read_u64::<byteorder::BigEndian>());
+            } else {
+                offset = try!(r.read_u32::<byteorder::BigEndian>()) as u64;
+                size = try!(r.read_u32::<byteorder::BigEndian>()) as u64;
+            }
+            let align = try!(r.read_u32::<byteorder::BigEndian>());
+            if magic == types::FAT_MAGIC_64 {
+                try!(r.read_u32::<byteorder::BigEndian>());
+            }
+
+            archs.push(types::FatArch {
+                cpu_type: cpu_type,
+                cpu_subtype: cpu_subtype,
+                offset: offset,
+                size: size,
+                align: align,
+            });
+        }
+
+        Ok(FatFile { archs: archs })
+    }
+
+    pub fn archs(&self) -> &[types::FatArch] {
+        &self.archs
+    }
+
+    /// Read the raw bytes of the slice at `index` of the architecture list
+    pub fn slice_data<R: io::Read + io::Seek>(&self, r: &mut R, index: usize) -> Result<Vec<u8>, Box<error::Error>> {
+        let arch = match self.archs.get(index) {
+            Some(x) => x,
+            None => return Err(Box::new(Error::from("invalid slice index"))),
+        };
+        try!(r.seek(io::SeekFrom::Start(arch.offset)));
+        let mut buf = Vec::new();
+        try!(io::Read::by_ref(r).take(arch.size).read_to_end(&mut buf));
+        if buf.len() as u64 != arch.size {
+            try!(Err(Error::from("truncated slice")));
+        }
+        Ok(buf)
+    }
+
+    /// Parse the slice at `index` of the architecture list as a thin Mach-O file
+    pub fn slice<R: io::Read + io::Seek>(&self, r: &mut R, index: usize) -> Result<File, Box<error::Error>> {
+        let buf = try!(self.slice_data(r, index));
+        File::parse(&mut io::Cursor::new(buf))
+    }
+
+    /// Parse every slice of the universal binary. Each slice succeeds or
+    /// fails on its own, in the order of the architecture list.
+    pub fn slices<R: io::Read + io::Seek>(&self, r: &mut R) -> Vec<Result<File, Box<error::Error>>> {
+        (0..self.archs.len()).map(|i| self.slice(r, i)).collect()
+    }
+}
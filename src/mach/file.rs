@@ -3,9 +3,14 @@ use std::io;
 use std::error;
 use std::collections::HashMap;
 use mach::types;
+use mach::dyld;
+use mach::fixups;
+use mach::codesign;
+use mach::reloc;
+use mach::objc;
 use byteorder;
-use byteorder::ReadBytesExt;
-use {Error, Section, Object};
+use byteorder::{ReadBytesExt, WriteBytesExt};
+use {non_fatal, read_file, Error, FileData, Section, Object};
 
 macro_rules! read_u8 {
     ($io:ident) => {
@@ -59,9 +64,434 @@ macro_rules! read_u64 {
     );
 }
 
+fn get_mach_string(data: &[u8], start: usize) -> String {
+    if start >= data.len() {
+        return String::new();
+    }
+    let end = match data[start..].iter().position(|x| *x == 0) {
+        Some(x) => start + x,
+        None => data.len(),
+    };
+    String::from_utf8_lossy(&data[start..end]).into_owned()
+}
+
+fn read_bytes<R: io::Read + io::Seek>(r: &mut R, off: u64, size: u64) -> Result<Vec<u8>, Box<error::Error>> {
+    try!(r.seek(io::SeekFrom::Start(off)));
+    let mut buf = Vec::new();
+    try!(io::Read::by_ref(r).take(size).read_to_end(&mut buf));
+    Ok(buf)
+}
+
+fn read_symtab<R: io::Read + io::Seek>(r: &mut R, data: types::Data, class: types::Class,
+                                       symoff: u32, nsyms: u32, stroff: u32, strsize: u32)
+                                       -> Result<Vec<types::Symbol>, Box<error::Error>> {
+    try!(r.seek(io::SeekFrom::Start(stroff as u64)));
+    let mut strtab = Vec::new();
+    try!(io::Read::by_ref(r).take(strsize as u64).read_to_end(&mut strtab));
+
+    try!(r.seek(io::SeekFrom::Start(symoff as u64)));
+    let mut symtab = Vec::new();
+    for _ in 0..nsyms {
+        let n_strx = try!(read_u32!(data, r));
+        let n_type = try!(read_u8!(r));
+        let n_sect = try!(read_u8!(r));
+        let n_desc = try!(read_u16!(data, r));
+        let value = match class {
+            types::MACH_CLASS_32 => try!(read_u32!(data, r)) as u64,
+            types::MACH_CLASS_64 => try!(read_u64!(data, r)),
+            _ => unreachable!(),
+        };
+        symtab.push(types::Symbol {
+            name: get_mach_string(&strtab, n_strx as usize),
+            n_type: n_type,
+            n_sect: n_sect,
+            n_desc: n_desc,
+            value: value,
+        });
+    }
+    Ok(symtab)
+}
+
+fn read_dysymtab<R: io::Read + io::Seek>(r: &mut R, data: types::Data, class: types::Class,
+                                         cmd: types::DysymtabCommand, strtab: &[u8])
+                                         -> Result<types::Dysymtab, Box<error::Error>> {
+    try!(r.seek(io::SeekFrom::Start(cmd.indirectsymoff as u64)));
+    let mut indirect_symbols = Vec::new();
+    for _ in 0..cmd.nindirectsyms {
+        indirect_symbols.push(try!(read_u32!(data, r)));
+    }
+
+    try!(r.seek(io::SeekFrom::Start(cmd.tocoff as u64)));
+    let mut toc = Vec::new();
+    for _ in 0..cmd.ntoc {
+        toc.push(types::TocEntry {
+            symbol_index: try!(read_u32!(data, r)),
+            module_index: try!(read_u32!(data, r)),
+        });
+    }
+
+    try!(r.seek(io::SeekFrom::Start(cmd.modtaboff as u64)));
+    let mut modules = Vec::new();
+    for _ in 0..cmd.nmodtab {
+        let mut f = [0u32; 11];
+        for x in f.iter_mut() {
+            *x = try!(read_u32!(data, r));
+        }
+        // dylib_module_64 moves the address after the size and widens it
+        let (addr, size) = match class {
+            types::MACH_CLASS_32 => {
+                let addr = try!(read_u32!(data, r)) as u64;
+                (addr, try!(read_u32!(data, r)))
+            }
+            _ => {
+                let size = try!(read_u32!(data, r));
+                (try!(read_u64!(data, r)), size)
+            }
+        };
+        modules.push(types::Module {
+            name: get_mach_string(strtab, f[0] as usize),
+            iextdefsym: f[1],
+            nextdefsym: f[2],
+            irefsym: f[3],
+            nrefsym: f[4],
+            ilocalsym: f[5],
+            nlocalsym: f[6],
+            iextrel: f[7],
+            nextrel: f[8],
+            iinit_iterm: f[9],
+            ninit_nterm: f[10],
+            objc_module_info_addr: addr,
+            objc_module_info_size: size,
+        });
+    }
+
+    Ok(types::Dysymtab {
+        cmd: cmd,
+        indirect_symbols: indirect_symbols,
+        toc: toc,
+        modules: modules,
+    })
+}
+
+/// Name the entries of stub and symbol pointer sections. Each such section
+/// starts at indirect symbol table index reserved1; stubs are reserved2
+/// bytes long and pointers are pointer sized.
+fn name_indirect_symbols(dysymtab: &types::Dysymtab, segments: &[types::SegmentCommand],
+                         symtab: &[types::Symbol], ptr_size: u64) -> Vec<types::IndirectSymbol> {
+    let mut ret = Vec::new();
+    for shdr in segments.iter().flat_map(|x| x.sections.iter()) {
+        let entry_size = match shdr.sect_type() {
+            types::S_SYMBOL_STUBS => shdr.reserved2 as u64,
+            types::S_NON_LAZY_SYMBOL_POINTERS | types::S_LAZY_SYMBOL_POINTERS |
+            types::S_LAZY_DYLIB_SYMBOL_POINTERS | types::S_THREAD_LOCAL_VARIABLE_POINTERS => ptr_size,
+            _ => continue,
+        };
+        // Skip corrupt sections whose entries would wrap around the address space
+        if entry_size == 0 || shdr.addr.checked_add(shdr.size).is_none() {
+            continue;
+        }
+        for i in 0..shdr.size / entry_size {
+            let slot = (shdr.reserved1 as usize).checked_add(i as usize);
+            let index = match slot.and_then(|x| dysymtab.indirect_symbols.get(x)) {
+                Some(x) => *x,
+                None => break,
+            };
+            let symbol = if index & (types::INDIRECT_SYMBOL_LOCAL | types::INDIRECT_SYMBOL_ABS) != 0 {
+                None
+            } else {
+                symtab.get(index as usize).map(|x| x.name.clone())
+            };
+            ret.push(types::IndirectSymbol {
+                address: shdr.addr + i * entry_size,
+                seg_name: shdr.seg_name.clone(),
+                sect_name: shdr.sect_name.clone(),
+                index: index,
+                symbol: symbol,
+            });
+        }
+    }
+    ret
+}
+
+/// Convert the non-stab nlist entries to the format independent representation
+fn common_symbols(symtab: &[types::Symbol], segments: &[types::SegmentCommand]) -> Vec<::Symbol> {
+    // n_sect is a 1-based ordinal over the sections of all segments
+    let sections: Vec<&types::SectionHeader> = segments.iter().flat_map(|x| x.sections.iter()).collect();
+    let mut ret = Vec::new();
+    for sym in symtab.iter().filter(|x| !x.is_stab()) {
+        let shdr = if sym.sym_type() == types::N_SECT && sym.n_sect != 0 {
+            sections.get(sym.n_sect as usize - 1).map(|x| *x)
+        } else {
+            None
+        };
+        let is_common = sym.is_undefined() && sym.is_external() && sym.value != 0;
+        let kind = match shdr {
+            _ if is_common => ::SymbolKind::Common,
+            Some(x) if x.sect_type() == types::S_THREAD_LOCAL_VARIABLES => ::SymbolKind::Tls,
+            Some(x) if x.flags & (types::S_ATTR_PURE_INSTRUCTIONS | types::S_ATTR_SOME_INSTRUCTIONS) != 0 => ::SymbolKind::Text,
+            Some(_) => ::SymbolKind::Data,
+            None => ::SymbolKind::Unknown,
+        };
+        let binding = if !sym.is_external() && !sym.is_private_extern() {
+            ::SymbolBinding::Local
+        } else if sym.is_weak_def() || sym.is_weak_ref() {
+            ::SymbolBinding::Weak
+        } else {
+            ::SymbolBinding::Global
+        };
+        ret.push(::Symbol {
+            name: sym.name.clone(),
+            address: sym.value,
+            size: 0,
+            kind: kind,
+            binding: binding,
+            visibility: if sym.is_private_extern() { ::SymbolVisibility::Hidden } else { ::SymbolVisibility::Default },
+            defined: !sym.is_undefined() && sym.sym_type() != types::N_INDR,
+            section: shdr.map(|x| x.sect_name.clone()),
+        });
+    }
+    ret
+}
+
+/// Collect the imported symbols from the chained fixups imports table, the
+/// dyld binding opcodes, or for images without either the undefined symbols
+fn common_imports(binds: &[types::Bind], chained_fixups: Option<&types::ChainedFixups>,
+                  symtab: &[types::Symbol], dylibs: &[types::Dylib]) -> Vec<::Import> {
+    let dylib = |ordinal: i64| if ordinal > 0 { dylibs.get(ordinal as usize - 1) } else { None };
+    let mut ret: Vec<::Import> = Vec::new();
+    let mut seen: HashMap<(String, i64), usize> = HashMap::new();
+    {
+        let mut add = |name: &str, ordinal: i64, weak: bool, address: Option<u64>| {
+            let key = (String::from(name), ordinal);
+            if let Some(&i) = seen.get(&key) {
+                // Bound at several locations
+                ret[i].address = None;
+                ret[i].weak &= weak;
+                return;
+            }
+            let lib = dylib(ordinal);
+            seen.insert(key, ret.len());
+            ret.push(::Import {
+                library: lib.map(|x| x.name.clone()),
+                name: Some(String::from(name)),
+                ordinal: None,
+                version: None,
+                weak: weak || lib.map(|x| x.is_weak()).unwrap_or(false),
+                delay: lib.map(|x| x.is_lazy()).unwrap_or(false),
+                address: address,
+            });
+        };
+        if let Some(fixups) = chained_fixups {
+            for (i, import) in fixups.imports.iter().enumerate() {
+                let mut locations = fixups.binds.iter().filter(|x| x.import as usize == i);
+                let address = match (locations.next(), locations.next()) {
+                    (Some(x), None) => Some(x.address),
+                    _ => None,
+                };
+                add(&import.name, import.library_ordinal, import.weak_import, address);
+            }
+        } else if !binds.is_empty() {
+            // Weak binds coalesce weak definitions rather than import anything
+            for bind in binds.iter().filter(|x| x.kind != types::BindKind::Weak) {
+                add(&bind.symbol_name, bind.library_ordinal, bind.is_weak_import(), Some(bind.address));
+            }
+        } else {
+            for sym in symtab.iter().filter(|x| x.is_undefined() && x.is_external() && x.value == 0) {
+                add(&sym.name, sym.library_ordinal() as i64, sym.is_weak_ref(), None);
+            }
+        }
+    }
+    ret
+}
+
+/// Convert the export trie, or for images without one the external defined
+/// symbols, to the format independent representation
+fn common_exports(exports: &[types::Export], has_trie: bool, symtab: &[types::Symbol]) -> Vec<::Export> {
+    if has_trie {
+        return exports.iter()
+            .map(|x| ::Export {
+                name: Some(x.name.clone()),
+                ordinal: None,
+                address: x.address,
+                version: None,
+                weak: x.is_weak(),
+                forwarder: x.reexport.as_ref().map(|y| {
+                    let name = if y.name.is_empty() { &x.name } else { &y.name };
+                    format!("{}.{}", y.library.as_deref().unwrap_or(""), name)
+                }),
+            })
+            .collect();
+    }
+    symtab.iter()
+        .filter(|x| !x.is_stab() && x.is_external() && !x.is_private_extern())
+        .filter(|x| x.sym_type() == types::N_SECT || x.sym_type() == types::N_ABS)
+        .map(|x| ::Export {
+            name: Some(x.name.clone()),
+            ordinal: None,
+            address: x.value,
+            version: None,
+            weak: x.is_weak_def(),
+            forwarder: None,
+        })
+        .collect()
+}
+
+/// Convert the section relocations and the dyld rebase, bind and chained
+/// fixup records to the format independent representation
+fn common_relocations(cpu_type: types::Machine, segments: &[types::SegmentCommand],
+                      relocations: &HashMap<(String, String), Vec<types::Relocation>>,
+                      rebases: &[types::Rebase], binds: &[types::Bind],
+                      chained_fixups: Option<&types::ChainedFixups>) -> Vec<::Relocation> {
+    let sections: Vec<&types::SectionHeader> = segments.iter().flat_map(|x| x.sections.iter()).collect();
+    let section_at = |addr: u64| sections.iter()
+        .find(|x| addr >= x.addr && addr - x.addr < x.size)
+        .map(|x| x.sect_name.clone());
+    let mut ret = Vec::new();
+    // Section order rather than hash order
+    for shdr in sections.iter() {
+        let relocs = match relocations.get(&(shdr.seg_name.clone(), shdr.sect_name.clone())) {
+            Some(x) => x,
+            None => continue,
+        };
+        for reloc in relocs.iter() {
+            // Non-extern relocations target a 1-based section ordinal
+            let symbol = if reloc.is_extern || reloc.scattered || reloc.symbolnum == types::R_ABS {
+                reloc.symbol.clone()
+            } else {
+                sections.get(reloc.symbolnum as usize - 1).map(|x| x.sect_name.clone())
+            };
+            ret.push(::Relocation {
+                address: shdr.addr.wrapping_add(reloc.address as u64),
+                section: Some(shdr.sect_name.clone()),
+                kind: reloc.category(cpu_type),
+                raw_type: reloc.r_type as u32,
+                symbol: symbol,
+                addend: reloc.addend,
+            });
+        }
+    }
+    for rebase in rebases.iter() {
+        ret.push(::Relocation {
+            address: rebase.address,
+            section: section_at(rebase.address),
+            kind: ::RelocationKind::BaseRelative,
+            raw_type: rebase.rebase_type as u32,
+            symbol: None,
+            addend: 0,
+        });
+    }
+    for bind in binds.iter() {
+        let kind = match bind.kind {
+            types::BindKind::Lazy => ::RelocationKind::Plt,
+            _ if bind.bind_type == types::BIND_TYPE_TEXT_PCREL32 => ::RelocationKind::PcRelative,
+            _ => ::RelocationKind::Absolute,
+        };
+        ret.push(::Relocation {
+            address: bind.address,
+            section: section_at(bind.address),
+            kind: kind,
+            raw_type: bind.bind_type as u32,
+            symbol: Some(bind.symbol_name.clone()),
+            addend: bind.addend,
+        });
+    }
+    if let Some(fixups) = chained_fixups {
+        // The raw type of a chained fixup is its segment's pointer format
+        let format = |seg_index: u32| fixups.starts.iter()
+            .find(|x| x.seg_index == seg_index)
+            .map(|x| x.pointer_format as u32)
+            .unwrap_or(0);
+        for rebase in fixups.rebases.iter() {
+            ret.push(::Relocation {
+                address: rebase.address,
+                section: section_at(rebase.address),
+                kind: ::RelocationKind::BaseRelative,
+                raw_type: format(rebase.seg_index),
+                symbol: None,
+                addend: 0,
+            });
+        }
+        for bind in fixups.binds.iter() {
+            ret.push(::Relocation {
+                address: bind.address,
+                section: section_at(bind.address),
+                kind: ::RelocationKind::Absolute,
+                raw_type: format(bind.seg_index),
+                symbol: Some(bind.symbol_name.clone()),
+                addend: bind.addend,
+            });
+        }
+    }
+    ret
+}
+
+/// Extract the initial program counter from a thread command's flavor/state list
+fn read_thread_pc(data: types::Data, cpu_type: types::Machine, body: &[u8]) -> Result<Option<u64>, Box<error::Error>> {
+    let mut c = io::Cursor::new(body);
+    while (c.position() as usize) + 8 <= body.len() {
+        let mut flavor = try!(read_u32!(data, c));
+        let mut count = try!(read_u32!(data, c));
+        let start = c.position();
+        if cpu_type == types::PM_X86 || cpu_type == types::PM_X86_64 {
+            if flavor == types::X86_THREAD_STATE {
+                flavor = try!(read_u32!(data, c));
+                count = try!(read_u32!(data, c));
+            }
+            // eip is register 10 of the i386 state, rip register 16 of the x86_64 state
+            if flavor == types::X86_THREAD_STATE32 {
+                try!(c.seek(io::SeekFrom::Current(10 * 4)));
+                return Ok(Some(try!(read_u32!(data, c)) as u64));
+            } else if flavor == types::X86_THREAD_STATE64 {
+                try!(c.seek(io::SeekFrom::Current(16 * 8)));
+                return Ok(Some(try!(read_u64!(data, c))));
+            }
+        } else if cpu_type == types::PM_ARM && flavor == types::ARM_THREAD_STATE {
+            // r0-r12, sp, lr, pc
+            try!(c.seek(io::SeekFrom::Current(15 * 4)));
+            return Ok(Some(try!(read_u32!(data, c)) as u64));
+        } else if cpu_type == types::PM_ARM64 && flavor == types::ARM_THREAD_STATE64 {
+            // x0-x28, fp, lr, sp, pc
+            try!(c.seek(io::SeekFrom::Current(32 * 8)));
+            return Ok(Some(try!(read_u64!(data, c))));
+        }
+        try!(c.seek(io::SeekFrom::Start(start + count as u64 * 4)));
+    }
+    Ok(None)
+}
+
 pub struct File {
     hdr: types::FileHeader,
-    sections: HashMap<String, Section>,
+    sections: Vec<Section>,
+    segments: Vec<types::SegmentCommand>,
+    symbols: HashMap<String, u64>,
+    symtab: Vec<types::Symbol>,
+    common_symbols: Vec<::Symbol>,
+    common_segments: Vec<::Segment>,
+    cmds: Vec<types::LoadCommand>,
+    dylibs: Vec<types::Dylib>,
+    id_dylib: Option<types::Dylib>,
+    rpaths: Vec<String>,
+    entry: Option<types::EntryPoint>,
+    entry_addr: Option<u64>,
+    uuid: Option<[u8; 16]>,
+    build_versions: Vec<types::BuildVersion>,
+    version_min: Option<types::VersionMin>,
+    dyld_info: Option<types::DyldInfo>,
+    rebases: Vec<types::Rebase>,
+    binds: Vec<types::Bind>,
+    chained_fixups: Option<types::ChainedFixups>,
+    exports: Vec<types::Export>,
+    code_signature: Option<types::CodeSignature>,
+    dysymtab: Option<types::Dysymtab>,
+    indirect_symbols: Vec<types::IndirectSymbol>,
+    relocations: HashMap<(String, String), Vec<types::Relocation>>,
+    function_starts: Vec<u64>,
+    data_in_code: Vec<types::DataInCode>,
+    libraries: Vec<String>,
+    common_imports: Vec<::Import>,
+    common_exports: Vec<::Export>,
+    common_relocations: Vec<::Relocation>,
+    errors: Vec<Error>,
 }
 
 impl File {
@@ -98,76 +528,307 @@ impl File {
 
         let cputype = types::Machine(try!(read_i32!(data, r)));
 
-        let cpu_subtype = try!(read_u32!(data, r)) ^ 0x80000000;
+        let cpu_subtype = try!(read_u32!(data, r)) ^ types::CPU_SUBTYPE_LIB64;
         let file_type = try!(read_u32!(data, r));
         let ncmds = try!(read_u32!(data, r));
         let sizeof_cmds = try!(read_u32!(data, r));
         let flags = try!(read_u32!(data, r));
-        try!(read_u32!(data, r));
+        if class == types::MACH_CLASS_64 {
+            try!(read_u32!(data, r));
+        }
+
+        // Each load command is read into its own buffer, so refuse sizes the
+        // file can't hold before allocating any of them
+        let cmds_start = try!(r.stream_position());
+        let file_len = try!(r.seek(io::SeekFrom::End(0)));
+        try!(r.seek(io::SeekFrom::Start(cmds_start)));
+        if sizeof_cmds as u64 > file_len - cmds_start {
+            try!(Err(Error::from("load commands extend past the end of the file")));
+        }
 
         let mut cmds = Vec::new();
-        let mut sections = HashMap::new();
+        let mut sections = Vec::new();
+        let mut segments = Vec::new();
+        let mut symtab_cmd = None;
+        let mut dysymtab_cmd = None;
+        let mut dylibs = Vec::new();
+        let mut id_dylib = None;
+        let mut rpaths = Vec::new();
+        let mut entry = None;
+        let mut uuid = None;
+        let mut build_versions = Vec::new();
+        let mut version_min = None;
+        let mut dyld_info = None;
+        let mut chained_fixups_cmd = None;
+        let mut exports_trie_cmd = None;
+        let mut code_signature_cmd = None;
+        let mut function_starts_cmd = None;
+        let mut data_in_code_cmd = None;
 
-        let mut cur_point = 0;
+        // Only the header, load commands and segments are required; the
+        // rest of the file is decoded on a best effort basis
+        let mut errors = Vec::new();
+        let mut cmds_left = sizeof_cmds;
         for _ in 0..ncmds {
             let cmd = try!(read_u32!(data, r));
             let size = try!(read_u32!(data, r));
-            cur_point += 8;
-            if cmd == 0x19 {
-                //Read LC_SECTION
+            if size < 8 || size > cmds_left {
+                try!(Err(Error::from("invalid load command size")));
+            }
+            cmds_left -= size;
+            let mut body = vec![0u8; size as usize - 8];
+            try!(r.read_exact(&mut body));
+            let mut c = io::Cursor::new(body.as_slice());
+
+            let seg_cmd = match class {
+                types::MACH_CLASS_32 => types::LC_SEGMENT,
+                _ => types::LC_SEGMENT_64,
+            };
+            if cmd == seg_cmd {
+                //Read LC_SEGMENT / LC_SEGMENT_64
                 let mut name_buf = [0u8; 16];
-                try!(r.read(&mut name_buf));
-                cur_point += 16;
-                let mut seg_name = String::from_utf8(name_buf.to_vec()).unwrap();
-                seg_name = String::from(seg_name.trim_matches('\0'));
-                if seg_name != "__PAGEZERO" {
-                    let mut i = 0;
-                    let vm_addr = try!(read_u64!(data, r));
-                    let vm_size = try!(read_u64!(data, r));
-                    let file_off = try!(read_u64!(data, r));
-                    let filesize = try!(read_u64!(data, r));
-                    let maxprot = try!(read_i32!(data, r));
-                    let initprot = try!(read_i32!(data, r));
-                    let nsects = try!(read_u32!(data, r));
-                    let seg_flags = try!(read_u32!(data, r));
-                    cur_point += 48;
-                    while i < nsects {
-                        //Read Section
-                        try!(r.read(&mut name_buf));
-                        let mut sect_name = String::from_utf8(name_buf.to_vec()).unwrap();
-                        sect_name = String::from(sect_name.trim_matches('\0'));
-                        try!(r.read(&mut name_buf));
-                        cur_point += 32;
-                        let mut sect_seg_name = String::from_utf8(name_buf.to_vec()).unwrap();
-                        sect_seg_name = String::from(sect_seg_name.trim_matches('\0'));
-
-                        let addr = try!(read_u64!(data, r));
-                        let size = try!(read_u64!(data, r));
-                        let offset = try!(read_u32!(data, r));
-                        let align = try!(read_u32!(data, r));
-                        let reloff = try!(read_u32!(data, r));
-                        let nreloc = try!(read_u32!(data, r));
-                        let flags = try!(read_u32!(data, r));
-
-                        let _ = try!(read_u64!(data, r));
-                        let _ = try!(read_u32!(data, r));
-
-                        cur_point += 48;
-
-                        let t_sect = Section {
-                            name: sect_name,
-                            addr: addr,
-                            offset: offset as u64,
-                            size: size,
-                            data: Vec::new(),
-                        };
-                        sections.insert(t_sect.name.clone(), t_sect);
-                        i += 1;
+                try!(c.read_exact(&mut name_buf));
+                let seg_name = get_mach_string(&name_buf, 0);
+                let vm_addr: u64;
+                let vm_size: u64;
+                let file_off: u64;
+                let filesize: u64;
+                match class {
+                    types::MACH_CLASS_32 => {
+                        vm_addr = try!(read_u32!(data, c)) as u64;
+                        vm_size = try!(read_u32!(data, c)) as u64;
+                        file_off = try!(read_u32!(data, c)) as u64;
+                        filesize = try!(read_u32!(data, c)) as u64;
+                    }
+                    types::MACH_CLASS_64 => {
+                        vm_addr = try!(read_u64!(data, c));
+                        vm_size = try!(read_u64!(data, c));
+                        file_off = try!(read_u64!(data, c));
+                        filesize = try!(read_u64!(data, c));
+                    }
+                    _ => unreachable!(),
+                }
+                let maxprot = try!(read_i32!(data, c));
+                let initprot = try!(read_i32!(data, c));
+                let nsects = try!(read_u32!(data, c));
+                let seg_flags = try!(read_u32!(data, c));
+
+                let mut seg_sects = Vec::new();
+                for _ in 0..nsects {
+                    //Read Section
+                    try!(c.read_exact(&mut name_buf));
+                    let sect_name = get_mach_string(&name_buf, 0);
+                    try!(c.read_exact(&mut name_buf));
+                    let sect_seg_name = get_mach_string(&name_buf, 0);
+
+                    let addr: u64;
+                    let size: u64;
+                    match class {
+                        types::MACH_CLASS_32 => {
+                            addr = try!(read_u32!(data, c)) as u64;
+                            size = try!(read_u32!(data, c)) as u64;
+                        }
+                        types::MACH_CLASS_64 => {
+                            addr = try!(read_u64!(data, c));
+                            size = try!(read_u64!(data, c));
+                        }
+                        _ => unreachable!(),
+                    }
+                    let offset = try!(read_u32!(data, c));
+                    let align = try!(read_u32!(data, c));
+                    let reloff = try!(read_u32!(data, c));
+                    let nreloc = try!(read_u32!(data, c));
+                    let flags = try!(read_u32!(data, c));
+                    let reserved1 = try!(read_u32!(data, c));
+                    let reserved2 = try!(read_u32!(data, c));
+                    if class == types::MACH_CLASS_64 {
+                        let _ = try!(read_u32!(data, c));
                     }
+
+                    seg_sects.push(types::SectionHeader {
+                        sect_name: sect_name,
+                        seg_name: sect_seg_name,
+                        addr: addr,
+                        size: size,
+                        offset: offset,
+                        align: align,
+                        reloff: reloff,
+                        nreloc: nreloc,
+                        flags: flags,
+                        reserved1: reserved1,
+                        reserved2: reserved2,
+                    });
                 }
+
+                segments.push(types::SegmentCommand {
+                    seg_name: seg_name,
+                    vm_addr: vm_addr,
+                    vm_size: vm_size,
+                    file_off: file_off,
+                    filesize: filesize,
+                    maxprot: maxprot,
+                    initprot: initprot,
+                    flags: seg_flags,
+                    sections: seg_sects,
+                });
+            }
+            match cmd {
+                types::LC_SYMTAB => {
+                    let symoff = try!(read_u32!(data, c));
+                    let nsyms = try!(read_u32!(data, c));
+                    let stroff = try!(read_u32!(data, c));
+                    let strsize = try!(read_u32!(data, c));
+                    symtab_cmd = Some((symoff, nsyms, stroff, strsize));
+                }
+                types::LC_DYSYMTAB => {
+                    let mut f = [0u32; 18];
+                    for x in f.iter_mut() {
+                        *x = try!(read_u32!(data, c));
+                    }
+                    dysymtab_cmd = Some(types::DysymtabCommand {
+                        ilocalsym: f[0],
+                        nlocalsym: f[1],
+                        iextdefsym: f[2],
+                        nextdefsym: f[3],
+                        iundefsym: f[4],
+                        nundefsym: f[5],
+                        tocoff: f[6],
+                        ntoc: f[7],
+                        modtaboff: f[8],
+                        nmodtab: f[9],
+                        extrefsymoff: f[10],
+                        nextrefsyms: f[11],
+                        indirectsymoff: f[12],
+                        nindirectsyms: f[13],
+                        extreloff: f[14],
+                        nextrel: f[15],
+                        locreloff: f[16],
+                        nlocrel: f[17],
+                    });
+                }
+                types::LC_LOAD_DYLIB | types::LC_LOAD_WEAK_DYLIB | types::LC_REEXPORT_DYLIB |
+                types::LC_LAZY_LOAD_DYLIB | types::LC_LOAD_UPWARD_DYLIB | types::LC_ID_DYLIB => {
+                    let name_off = try!(read_u32!(data, c));
+                    let timestamp = try!(read_u32!(data, c));
+                    let current_version = try!(read_u32!(data, c));
+                    let compat_version = try!(read_u32!(data, c));
+                    let dylib = types::Dylib {
+                        cmd: cmd,
+                        name: get_mach_string(&body, (name_off as usize).saturating_sub(8)),
+                        timestamp: timestamp,
+                        current_version: types::Version(current_version),
+                        compat_version: types::Version(compat_version),
+                    };
+                    if cmd == types::LC_ID_DYLIB {
+                        id_dylib = Some(dylib);
+                    } else {
+                        dylibs.push(dylib);
+                    }
+                }
+                types::LC_MAIN => {
+                    let entryoff = try!(read_u64!(data, c));
+                    let stack_size = try!(read_u64!(data, c));
+                    entry = Some(types::EntryPoint::Main {
+                        entryoff: entryoff,
+                        stack_size: stack_size,
+                    });
+                }
+                types::LC_UNIXTHREAD => {
+                    if let Some(pc) = non_fatal(&mut errors, "thread state", read_thread_pc(data, cputype, &body)) {
+                        entry = Some(types::EntryPoint::UnixThread { pc: pc });
+                    }
+                }
+                types::LC_UUID => {
+                    let mut buf = [0u8; 16];
+                    try!(c.read_exact(&mut buf));
+                    uuid = Some(buf);
+                }
+                types::LC_BUILD_VERSION => {
+                    let platform = types::Platform(try!(read_u32!(data, c)));
+                    let minos = types::Version(try!(read_u32!(data, c)));
+                    let sdk = types::Version(try!(read_u32!(data, c)));
+                    let ntools = try!(read_u32!(data, c));
+                    let mut tools = Vec::new();
+                    for _ in 0..ntools {
+                        let tool = types::Tool(try!(read_u32!(data, c)));
+                        let version = types::Version(try!(read_u32!(data, c)));
+                        tools.push(types::BuildTool {
+                            tool: tool,
+                            version: version,
+                        });
+                    }
+                    build_versions.push(types::BuildVersion {
+                        platform: platform,
+                        minos: minos,
+                        sdk: sdk,
+                        tools: tools,
+                    });
+                }
+                types::LC_VERSION_MIN_MACOSX | types::LC_VERSION_MIN_IPHONEOS |
+                types::LC_VERSION_MIN_TVOS | types::LC_VERSION_MIN_WATCHOS => {
+                    let version = types::Version(try!(read_u32!(data, c)));
+                    let sdk = types::Version(try!(read_u32!(data, c)));
+                    version_min = Some(types::VersionMin {
+                        cmd: cmd,
+                        version: version,
+                        sdk: sdk,
+                    });
+                }
+                types::LC_DYLD_INFO | types::LC_DYLD_INFO_ONLY => {
+                    dyld_info = Some(types::DyldInfo {
+                        rebase_off: try!(read_u32!(data, c)),
+                        rebase_size: try!(read_u32!(data, c)),
+                        bind_off: try!(read_u32!(data, c)),
+                        bind_size: try!(read_u32!(data, c)),
+                        weak_bind_off: try!(read_u32!(data, c)),
+                        weak_bind_size: try!(read_u32!(data, c)),
+                        lazy_bind_off: try!(read_u32!(data, c)),
+                        lazy_bind_size: try!(read_u32!(data, c)),
+                        export_off: try!(read_u32!(data, c)),
+                        export_size: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_DYLD_CHAINED_FIXUPS => {
+                    chained_fixups_cmd = Some(types::LinkeditData {
+                        cmd: cmd,
+                        dataoff: try!(read_u32!(data, c)),
+                        datasize: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_DYLD_EXPORTS_TRIE => {
+                    exports_trie_cmd = Some(types::LinkeditData {
+                        cmd: cmd,
+                        dataoff: try!(read_u32!(data, c)),
+                        datasize: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_CODE_SIGNATURE => {
+                    code_signature_cmd = Some(types::LinkeditData {
+                        cmd: cmd,
+                        dataoff: try!(read_u32!(data, c)),
+                        datasize: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_FUNCTION_STARTS => {
+                    function_starts_cmd = Some(types::LinkeditData {
+                        cmd: cmd,
+                        dataoff: try!(read_u32!(data, c)),
+                        datasize: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_DATA_IN_CODE => {
+                    data_in_code_cmd = Some(types::LinkeditData {
+                        cmd: cmd,
+                        dataoff: try!(read_u32!(data, c)),
+                        datasize: try!(read_u32!(data, c)),
+                    });
+                }
+                types::LC_RPATH => {
+                    let path_off = try!(read_u32!(data, c));
+                    rpaths.push(get_mach_string(&body, (path_off as usize).saturating_sub(8)));
+                }
+                _ => {}
             }
-            try!(r.seek(io::SeekFrom::Current((size - cur_point) as i64)));
-            cur_point = 0;
             let t_cmd = types::LoadCommand {
                 cmd: cmd,
                 size: size,
@@ -175,16 +836,172 @@ impl File {
             cmds.push(t_cmd);
         }
 
-        let t_size = sections.get("__text").unwrap().size;
-        let mut data_buf = Vec::with_capacity(t_size as usize);
-        unsafe { data_buf.set_len(t_size as usize); }
-        try!(r.seek(io::SeekFrom::Start(sections.get("__text").unwrap().offset as u64)));
-        try!(r.read(&mut data_buf));
-        sections.get_mut("__text").unwrap().data = data_buf;
+        let text_addr = segments.iter().find(|x| x.seg_name == "__TEXT").map(|x| x.vm_addr.wrapping_sub(x.file_off));
+        let entry_addr = match entry {
+            Some(types::EntryPoint::Main { entryoff, .. }) => text_addr.map(|x| x.wrapping_add(entryoff)),
+            Some(types::EntryPoint::UnixThread { pc }) => Some(pc),
+            None => None,
+        };
+
+        let ptr_size = match class {
+            types::MACH_CLASS_32 => 4,
+            _ => 8,
+        };
+        let mut rebases = Vec::new();
+        let mut binds = Vec::new();
+        if let Some(ref info) = dyld_info {
+            let opcodes = try!(read_bytes(r, info.rebase_off as u64, info.rebase_size as u64));
+            rebases = non_fatal(&mut errors, "rebase opcodes", dyld::parse_rebases(&opcodes, &segments, ptr_size));
+            let streams = [(types::BindKind::Regular, "bind opcodes", info.bind_off, info.bind_size),
+                           (types::BindKind::Weak, "weak bind opcodes", info.weak_bind_off, info.weak_bind_size),
+                           (types::BindKind::Lazy, "lazy bind opcodes", info.lazy_bind_off, info.lazy_bind_size)];
+            for &(kind, what, off, size) in streams.iter() {
+                let opcodes = try!(read_bytes(r, off as u64, size as u64));
+                binds.extend(non_fatal(&mut errors, what,
+                                       dyld::parse_binds(&opcodes, kind, &segments, &dylibs, ptr_size)));
+            }
+        }
+
+        let mut chained_fixups = None;
+        if let Some(ref cmd) = chained_fixups_cmd {
+            let blob = try!(read_bytes(r, cmd.dataoff as u64, cmd.datasize as u64));
+            let base = text_addr.unwrap_or(0);
+            chained_fixups = non_fatal(&mut errors, "chained fixups",
+                                       fixups::parse_chained_fixups(r, &blob, &segments, &dylibs, base).map(Some));
+        }
+
+        let trie = match (&exports_trie_cmd, &dyld_info) {
+            (&Some(ref cmd), _) => Some((cmd.dataoff, cmd.datasize)),
+            (&None, &Some(ref info)) => Some((info.export_off, info.export_size)),
+            _ => None,
+        };
+        let mut exports = Vec::new();
+        if let Some((off, size)) = trie {
+            let blob = try!(read_bytes(r, off as u64, size as u64));
+            exports = non_fatal(&mut errors, "export trie",
+                                dyld::parse_export_trie(&blob, text_addr.unwrap_or(0), &dylibs));
+        }
+
+        let mut code_signature = None;
+        if let Some(ref cmd) = code_signature_cmd {
+            let blob = try!(read_bytes(r, cmd.dataoff as u64, cmd.datasize as u64));
+            code_signature = non_fatal(&mut errors, "code signature", codesign::parse_code_signature(&blob).map(Some));
+        }
+
+        let mut function_starts = Vec::new();
+        if let Some(ref cmd) = function_starts_cmd {
+            let blob = try!(read_bytes(r, cmd.dataoff as u64, cmd.datasize as u64));
+            function_starts = non_fatal(&mut errors, "function starts",
+                                        dyld::parse_function_starts(&blob, text_addr.unwrap_or(0)));
+        }
+
+        let mut data_in_code = Vec::new();
+        if let Some(ref cmd) = data_in_code_cmd {
+            let blob = try!(read_bytes(r, cmd.dataoff as u64, cmd.datasize as u64));
+            let mut c = io::Cursor::new(blob.as_slice());
+            for _ in 0..blob.len() / 8 {
+                let offset = try!(read_u32!(data, c));
+                data_in_code.push(types::DataInCode {
+                    offset: offset,
+                    length: try!(read_u16!(data, c)),
+                    kind: try!(read_u16!(data, c)),
+                    address: text_addr.unwrap_or(0).wrapping_add(offset as u64),
+                });
+            }
+        }
+
+        let mut symtab = Vec::new();
+        if let Some((symoff, nsyms, stroff, strsize)) = symtab_cmd {
+            symtab = non_fatal(&mut errors, "symbol table",
+                               read_symtab(r, data, class, symoff, nsyms, stroff, strsize));
+        }
+
+        let mut relocations = HashMap::new();
+        for shdr in segments.iter().flat_map(|x| x.sections.iter()) {
+            if shdr.nreloc == 0 {
+                continue;
+            }
+            let blob = try!(read_bytes(r, shdr.reloff as u64, shdr.nreloc as u64 * 8));
+            let what = format!("relocations of {},{}", shdr.seg_name, shdr.sect_name);
+            let relocs = non_fatal(&mut errors, &what, reloc::parse_relocations(&blob, data, cputype, &symtab));
+            relocations.insert((shdr.seg_name.clone(), shdr.sect_name.clone()), relocs);
+        }
+
+        let mut dysymtab = None;
+        let mut indirect_symbols = Vec::new();
+        if let Some(cmd) = dysymtab_cmd {
+            let strtab = match symtab_cmd {
+                Some((_, _, stroff, strsize)) => try!(read_bytes(r, stroff as u64, strsize as u64)),
+                None => Vec::new(),
+            };
+            dysymtab = non_fatal(&mut errors, "dynamic symbol table",
+                                 read_dysymtab(r, data, class, cmd, &strtab).map(Some));
+            if let Some(ref x) = dysymtab {
+                indirect_symbols = name_indirect_symbols(x, &segments, &symtab, ptr_size);
+            }
+        }
+
+        let mut symbols = HashMap::new();
+        for sym in symtab.iter() {
+            if !sym.is_stab() {
+                symbols.insert(sym.name.clone(), sym.value);
+            }
+        }
+
+        let file_data = try!(read_file(r));
+        for (seg, shdr) in segments.iter().flat_map(|x| x.sections.iter().map(move |y| (x, y))) {
+            // Zero-fill sections occupy memory but have no bytes in the file
+            let sect_type = shdr.sect_type();
+            let zerofill = sect_type == types::S_ZEROFILL || sect_type == types::S_GB_ZEROFILL ||
+                           sect_type == types::S_THREAD_LOCAL_ZEROFILL;
+            let debug = shdr.flags & types::S_ATTR_DEBUG != 0;
+            sections.push(Section {
+                name: shdr.sect_name.clone(),
+                addr: shdr.addr,
+                offset: shdr.offset as u64,
+                size: shdr.size,
+                data: if zerofill {
+                    FileData::default()
+                } else {
+                    FileData::new(&file_data, shdr.offset as u64, shdr.size)
+                },
+                alloc: !debug,
+                // Object files put every section in one unnamed rwx segment
+                write: if seg.seg_name.is_empty() { shdr.seg_name != "__TEXT" } else { seg.is_writable() },
+                execute: shdr.flags & (types::S_ATTR_PURE_INSTRUCTIONS | types::S_ATTR_SOME_INSTRUCTIONS) != 0,
+                bss: zerofill,
+                tls: sect_type >= types::S_THREAD_LOCAL_REGULAR && sect_type <= types::S_THREAD_LOCAL_INIT_FUNCTION_POINTERS,
+                debug: debug,
+                align: 1 << shdr.align.min(63),
+                header: Some(::SectionHeader::Mach(shdr.clone())),
+            });
+        }
+
+        let common_symbols = common_symbols(&symtab, &segments);
+        let libraries = dylibs.iter().map(|x| x.name.clone()).collect();
+        let common_imports = common_imports(&binds, chained_fixups.as_ref(), &symtab, &dylibs);
+        let common_exports = common_exports(&exports, trie.is_some(), &symtab);
+        let common_relocations = common_relocations(cputype, &segments, &relocations, &rebases, &binds,
+                                                    chained_fixups.as_ref());
+        let mut common_segments = Vec::new();
+        for seg in segments.iter() {
+            common_segments.push(::Segment {
+                name: Some(seg.seg_name.clone()),
+                addr: seg.vm_addr,
+                size: seg.vm_size,
+                offset: seg.file_off,
+                file_size: seg.filesize,
+                read: seg.is_readable(),
+                write: seg.is_writable(),
+                execute: seg.is_executable(),
+                data: FileData::new(&file_data, seg.file_off, seg.filesize),
+            });
+        }
 
         let x = File {
             hdr: types::FileHeader {
                 magic: magic,
+                class: class,
                 cpu_type: cputype,
                 cpu_subtype: cpu_subtype,
                 file_type: file_type,
@@ -194,32 +1011,276 @@ impl File {
                 data: data,
             },
             sections: sections,
+            segments: segments,
+            symbols: symbols,
+            common_symbols: common_symbols,
+            common_segments: common_segments,
+            symtab: symtab,
+            cmds: cmds,
+            dylibs: dylibs,
+            id_dylib: id_dylib,
+            rpaths: rpaths,
+            entry: entry,
+            entry_addr: entry_addr,
+            uuid: uuid,
+            build_versions: build_versions,
+            version_min: version_min,
+            dyld_info: dyld_info,
+            rebases: rebases,
+            binds: binds,
+            chained_fixups: chained_fixups,
+            exports: exports,
+            code_signature: code_signature,
+            dysymtab: dysymtab,
+            indirect_symbols: indirect_symbols,
+            relocations: relocations,
+            function_starts: function_starts,
+            data_in_code: data_in_code,
+            libraries: libraries,
+            common_imports: common_imports,
+            common_exports: common_exports,
+            common_relocations: common_relocations,
+            errors: errors,
         };
         Ok(x)
     }
-    pub fn sections(&self) -> &HashMap<String, Section> {
+    pub fn header(&self) -> &types::FileHeader {
+        &self.hdr
+    }
+    /// Errors from decoding the optional parts of the file, such as the
+    /// dyld opcode streams. Accessors for a part that failed to decode
+    /// return nothing for it.
+    pub fn decode_errors(&self) -> &[Error] {
+        &self.errors
+    }
+    /// Sections of all segments in load command order; index `n_sect - 1`
+    /// for symbols and non-extern relocations
+    pub fn sections(&self) -> &[Section] {
         &self.sections
     }
+    /// Segments in load command order, each with its section headers
+    pub fn segments(&self) -> &[types::SegmentCommand] {
+        &self.segments
+    }
+    /// Look up a section header by segment and section name, e.g.
+    /// ("__DATA", "__const")
+    pub fn section_header(&self, seg_name: &str, sect_name: &str) -> Option<&types::SectionHeader> {
+        self.segments.iter()
+            .flat_map(|x| x.sections.iter())
+            .find(|x| x.seg_name == seg_name && x.sect_name == sect_name)
+    }
+    pub fn symbols(&self) -> &HashMap<String, u64> {
+        &self.symbols
+    }
+    /// Full symbol table entries, including stabs, in file order
+    pub fn symbol_table(&self) -> &[types::Symbol] {
+        &self.symtab
+    }
+    /// LC_DYSYMTAB symbol ranges and tables
+    pub fn dysymtab(&self) -> Option<&types::Dysymtab> {
+        self.dysymtab.as_ref()
+    }
+    /// Entries of the stub and symbol pointer sections (__stubs, __got,
+    /// __la_symbol_ptr, ...) named through the indirect symbol table, in
+    /// section order
+    pub fn indirect_symbols(&self) -> &[types::IndirectSymbol] {
+        &self.indirect_symbols
+    }
+    /// Name of the symbol a stub or symbol pointer at `addr` refers to
+    pub fn indirect_symbol_name(&self, addr: u64) -> Option<&str> {
+        self.indirect_symbols.iter()
+            .find(|x| x.address == addr)
+            .and_then(|x| x.symbol.as_ref())
+            .map(|x| x.as_str())
+    }
+    pub fn load_commands(&self) -> &[types::LoadCommand] {
+        &self.cmds
+    }
+    /// Dependent libraries in load order; library ordinal N refers to
+    /// entry N - 1
+    pub fn dylibs(&self) -> &[types::Dylib] {
+        &self.dylibs
+    }
+    /// Install name of this file, if it is a dylib
+    pub fn id_dylib(&self) -> Option<&types::Dylib> {
+        self.id_dylib.as_ref()
+    }
+    /// Runpath search paths, in load command order
+    pub fn rpaths(&self) -> &[String] {
+        &self.rpaths
+    }
+    /// Raw LC_MAIN or LC_UNIXTHREAD entry point information
+    pub fn entry_command(&self) -> Option<&types::EntryPoint> {
+        self.entry.as_ref()
+    }
+    /// Virtual address of the entry point
+    pub fn entrypoint(&self) -> Option<u64> {
+        self.entry_addr
+    }
+    pub fn uuid(&self) -> Option<&[u8; 16]> {
+        self.uuid.as_ref()
+    }
+    /// LC_BUILD_VERSION entries; zippered Mac Catalyst binaries carry one
+    /// for macOS and one for Mac Catalyst
+    pub fn build_versions(&self) -> &[types::BuildVersion] {
+        &self.build_versions
+    }
+    /// Legacy LC_VERSION_MIN_* command, used before LC_BUILD_VERSION
+    pub fn version_min(&self) -> Option<&types::VersionMin> {
+        self.version_min.as_ref()
+    }
+    pub fn dyld_info(&self) -> Option<&types::DyldInfo> {
+        self.dyld_info.as_ref()
+    }
+    /// Rebase locations from the LC_DYLD_INFO rebase opcodes
+    pub fn rebases(&self) -> &[types::Rebase] {
+        &self.rebases
+    }
+    /// Bind records from the LC_DYLD_INFO bind, weak bind and lazy bind opcodes
+    pub fn binds(&self) -> &[types::Bind] {
+        &self.binds
+    }
+    pub fn chained_fixups(&self) -> Option<&types::ChainedFixups> {
+        self.chained_fixups.as_ref()
+    }
+    /// Exported symbols from the export trie, sorted by name
+    pub fn exports(&self) -> &[types::Export] {
+        &self.exports
+    }
+    /// Embedded code signature. A corrupt or unrecognised signature is
+    /// left out and reported by `decode_errors`.
+    pub fn code_signature(&self) -> Option<&types::CodeSignature> {
+        self.code_signature.as_ref()
+    }
+    /// Check every code directory of the embedded signature against the
+    /// file contents. `r` must be the stream this file was parsed from; for
+    /// a universal binary slice use `FatFile::slice_data`.
+    pub fn verify_code_signature<R: io::Read + io::Seek>(&self, r: &mut R)
+                                                         -> Result<Vec<types::SignatureVerification>, Box<error::Error>> {
+        let sig = match self.code_signature {
+            Some(ref x) => x,
+            None => return Err(Box::new(Error::from("no code signature"))),
+        };
+        let mut ret = Vec::new();
+        for cd in sig.code_directories.iter() {
+            ret.push(try!(codesign::verify(r, sig, cd)));
+        }
+        Ok(ret)
+    }
+    /// Relocation entries of a section, e.g. ("__TEXT", "__text"). Only
+    /// object files (MH_OBJECT) normally carry section relocations.
+    pub fn relocations(&self, seg_name: &str, sect_name: &str) -> &[types::Relocation] {
+        self.relocations.get(&(String::from(seg_name), String::from(sect_name)))
+            .map(|x| x.as_slice())
+            .unwrap_or(&[])
+    }
+    /// Objective-C classes, categories, protocols and selector references,
+    /// decoded from the __objc_* sections
+    pub fn objc_metadata(&self) -> Result<types::ObjcMetadata, Box<error::Error>> {
+        objc::parse_objc(self)
+    }
+    /// Swift types, protocols and conformances from the __swift5_* sections
+    pub fn swift_metadata(&self) -> Result<::swift::Metadata, Box<error::Error>> {
+        let data: Vec<(&str, u64, Vec<u8>)> = self.sections.iter()
+            .map(|x| (x.name(), x.addr(), self.fixup_data(x.addr(), x.data())))
+            .collect();
+        let sections: Vec<(&str, u64, &[u8])> = data.iter().map(|x| (x.0, x.1, x.2.as_slice())).collect();
+        let ptr_size = if self.hdr.class == types::MACH_CLASS_32 { 4 } else { 8 };
+        ::swift::parse_swift(&sections, "__swift5_", ptr_size)
+    }
+    /// Function start addresses from LC_FUNCTION_STARTS, in ascending order.
+    /// Unlike the symbol table these survive stripping.
+    pub fn function_starts(&self) -> &[u64] {
+        &self.function_starts
+    }
+    /// Data regions embedded in __TEXT, such as ARM jump tables, which a
+    /// disassembler should not decode as instructions
+    pub fn data_in_code(&self) -> &[types::DataInCode] {
+        &self.data_in_code
+    }
+    /// Copy of `data`, which is mapped at virtual address `addr`, with the
+    /// chained fixup pointers it contains rewritten the way dyld would for an
+    /// image loaded at its preferred address. Bound pointers are zeroed since
+    /// their targets live in other images.
+    pub fn fixup_data(&self, addr: u64, data: &[u8]) -> Vec<u8> {
+        let mut ret = data.to_vec();
+        if let Some(ref fixups) = self.chained_fixups {
+            let end = addr.wrapping_add(data.len() as u64);
+            let locations = fixups.rebases.iter().map(|x| (x.address, x.target))
+                .chain(fixups.binds.iter().map(|x| (x.address, 0)));
+            for (address, value) in locations {
+                if address >= addr && address.wrapping_add(8) <= end {
+                    let off = (address - addr) as usize;
+                    let mut buf = &mut ret[off..off + 8];
+                    let _ = buf.write_u64::<byteorder::LittleEndian>(value);
+                }
+            }
+        }
+        ret
+    }
+    /// Section data with chained fixups applied, see `fixup_data`
+    pub fn fixed_up_section_data(&self, name: &str) -> Option<Vec<u8>> {
+        self.get_section(name).map(|x| self.fixup_data(x.addr, &x.data))
+    }
 }
 
 impl Object for File {
     fn arch(&self) -> ::Arch {
-        let endian = match self.hdr.data {
-            types::MACH_DATA_2LSB => ::Endianness::Little,
-            types::MACH_DATA_2MSB => ::Endianness::Big,
-            _ => return ::Arch::Unknown,
-        };
-        match self.hdr.cpu_type {
-            types::PM_I386 => ::Arch::X86(::Width::W32),
-            types::PM_X86_64 => ::Arch::X86(::Width::W64),
-            types::PM_POWERPC => ::Arch::PPC(::Width::W32, endian),
-            types::PM_POWERPC64 => ::Arch::PPC(::Width::W64, endian),
-            _ => return ::Arch::Unknown,
-        }
+        self.hdr.cpu_type.arch(self.hdr.raw_cpu_subtype(), self.hdr.data)
     }
     fn get_section(&self, name: &str) -> Option<&Section> {
         let mut fmt_name = String::from(name.trim_matches('.'));
         fmt_name = String::from("__") + &fmt_name;
-        self.sections.get(fmt_name.as_str())
+        self.sections.iter().find(|x| x.name == fmt_name)
+    }
+    fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+    fn symbols(&self) -> &[::Symbol] {
+        &self.common_symbols
+    }
+    fn segments(&self) -> &[::Segment] {
+        &self.common_segments
+    }
+    fn entry(&self) -> Option<u64> {
+        self.entry_addr
+    }
+    fn image_base(&self) -> u64 {
+        // The segment mapping the start of the file (__TEXT), or for object
+        // files the lowest segment
+        self.segments.iter()
+            .find(|x| x.file_off == 0 && x.filesize != 0)
+            .or_else(|| self.segments.iter().filter(|x| x.seg_name != "__PAGEZERO").min_by_key(|x| x.vm_addr))
+            .map(|x| x.vm_addr)
+            .unwrap_or(0)
+    }
+    fn kind(&self) -> ::FileKind {
+        match self.hdr.file_type {
+            types::MH_EXECUTE | types::MH_PRELOAD => ::FileKind::Executable,
+            types::MH_DYLIB | types::MH_BUNDLE | types::MH_DYLINKER | types::MH_FVMLIB |
+            types::MH_DYLIB_STUB | types::MH_KEXT_BUNDLE => ::FileKind::SharedLibrary,
+            types::MH_OBJECT => ::FileKind::Relocatable,
+            types::MH_CORE => ::FileKind::Core,
+            _ => ::FileKind::Unknown,
+        }
+    }
+    fn is_position_independent(&self) -> bool {
+        match self.hdr.file_type {
+            types::MH_EXECUTE => self.hdr.flags & types::MH_PIE != 0,
+            types::MH_DYLIB | types::MH_BUNDLE | types::MH_DYLINKER | types::MH_KEXT_BUNDLE => true,
+            _ => false,
+        }
+    }
+    fn libraries(&self) -> &[String] {
+        &self.libraries
+    }
+    fn imports(&self) -> &[::Import] {
+        &self.common_imports
+    }
+    fn exports(&self) -> &[::Export] {
+        &self.common_exports
+    }
+    fn relocations(&self) -> &[::Relocation] {
+        &self.common_relocations
     }
 }
@@ -0,0 +1,291 @@
+use std::io::prelude::*;
+use std::io;
+use std::error;
+use byteorder;
+use byteorder::ReadBytesExt;
+use mach::types;
+use mach::dyld;
+use Error;
+
+// Chained fixups are only emitted for little endian targets, so unlike the
+// rest of the Mach-O parser these structures are always read little endian.
+
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// Convert a chained import library ordinal of `bits` width to the
+/// BIND_SPECIAL_DYLIB_* convention used elsewhere
+fn import_ordinal(value: u64, bits: u32) -> i64 {
+    if value >= (1 << bits) - 0x10 {
+        sign_extend(value, bits)
+    } else {
+        value as i64
+    }
+}
+
+fn read_imports(blob: &[u8], hdr: &types::ChainedFixupsHeader, dylibs: &[types::Dylib])
+                -> Result<Vec<types::ChainedImport>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if hdr.symbols_format != 0 {
+        // zlib compressed symbol names are not supported
+        try!(Err(Error::from("unsupported chained fixups symbols format")));
+    }
+    let symbols = match blob.get(hdr.symbols_offset as usize..) {
+        Some(x) => x,
+        None => return Err(Box::new(Error::from("invalid chained fixups symbols offset"))),
+    };
+    let mut c = io::Cursor::new(blob);
+    try!(c.seek(io::SeekFrom::Start(hdr.imports_offset as u64)));
+    for _ in 0..hdr.imports_count {
+        let library_ordinal;
+        let weak_import;
+        let name_offset;
+        let mut addend = 0;
+        match hdr.imports_format {
+            types::DYLD_CHAINED_IMPORT | types::DYLD_CHAINED_IMPORT_ADDEND => {
+                let raw = try!(c.read_u32::<byteorder::LittleEndian>()) as u64;
+                library_ordinal = import_ordinal(raw & 0xff, 8);
+                weak_import = (raw >> 8) & 1 != 0;
+                name_offset = raw >> 9;
+                if hdr.imports_format == types::DYLD_CHAINED_IMPORT_ADDEND {
+                    addend = try!(c.read_i32::<byteorder::LittleEndian>()) as i64;
+                }
+            }
+            types::DYLD_CHAINED_IMPORT_ADDEND64 => {
+                let raw = try!(c.read_u64::<byteorder::LittleEndian>());
+                library_ordinal = import_ordinal(raw & 0xffff, 16);
+                weak_import = (raw >> 16) & 1 != 0;
+                name_offset = raw >> 32;
+                addend = try!(c.read_i64::<byteorder::LittleEndian>());
+            }
+            _ => return Err(Box::new(Error::from("invalid chained fixups imports format"))),
+        }
+        let mut pos = name_offset as usize;
+        ret.push(types::ChainedImport {
+            library_ordinal: library_ordinal,
+            library: if library_ordinal > 0 {
+                dylibs.get(library_ordinal as usize - 1).map(|x| x.name.clone())
+            } else {
+                None
+            },
+            weak_import: weak_import,
+            name: try!(dyld::read_cstr(symbols, &mut pos)),
+            addend: addend,
+        });
+    }
+    Ok(ret)
+}
+
+fn read_starts(blob: &[u8], hdr: &types::ChainedFixupsHeader)
+               -> Result<Vec<types::ChainedStarts>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    let mut c = io::Cursor::new(blob);
+    try!(c.seek(io::SeekFrom::Start(hdr.starts_offset as u64)));
+    let seg_count = try!(c.read_u32::<byteorder::LittleEndian>());
+    let mut seg_info_offsets = Vec::new();
+    for _ in 0..seg_count {
+        seg_info_offsets.push(try!(c.read_u32::<byteorder::LittleEndian>()));
+    }
+    for (i, off) in seg_info_offsets.into_iter().enumerate() {
+        if off == 0 {
+            continue;
+        }
+        try!(c.seek(io::SeekFrom::Start(hdr.starts_offset as u64 + off as u64)));
+        let _ = try!(c.read_u32::<byteorder::LittleEndian>());
+        let page_size = try!(c.read_u16::<byteorder::LittleEndian>());
+        let pointer_format = try!(c.read_u16::<byteorder::LittleEndian>());
+        let segment_offset = try!(c.read_u64::<byteorder::LittleEndian>());
+        let max_valid_pointer = try!(c.read_u32::<byteorder::LittleEndian>());
+        let page_count = try!(c.read_u16::<byteorder::LittleEndian>());
+        let mut page_starts = Vec::new();
+        for _ in 0..page_count {
+            page_starts.push(try!(c.read_u16::<byteorder::LittleEndian>()));
+        }
+        ret.push(types::ChainedStarts {
+            seg_index: i as u32,
+            page_size: page_size,
+            pointer_format: pointer_format,
+            segment_offset: segment_offset,
+            max_valid_pointer: max_valid_pointer,
+            page_starts: page_starts,
+        });
+    }
+    Ok(ret)
+}
+
+/// Decoded chained pointer
+enum ChainedPtr {
+    Rebase(u64, Option<types::PtrAuth>),
+    Bind(u32, i64, Option<types::PtrAuth>),
+}
+
+/// Decode one chained pointer, returning the delta to the next pointer (in
+/// strides) and its contents. `base` is the address of the mach header, which
+/// offset-based formats are relative to.
+fn decode_ptr(format: u16, raw: u64, base: u64) -> Option<(u64, ChainedPtr)> {
+    match format {
+        types::DYLD_CHAINED_PTR_64 | types::DYLD_CHAINED_PTR_64_OFFSET => {
+            let next = (raw >> 51) & 0xfff;
+            if raw >> 63 != 0 {
+                let ordinal = (raw & 0xffffff) as u32;
+                let addend = ((raw >> 24) & 0xff) as i64;
+                Some((next, ChainedPtr::Bind(ordinal, addend, None)))
+            } else {
+                let mut target = raw & 0xf_ffff_ffff;
+                if format == types::DYLD_CHAINED_PTR_64_OFFSET {
+                    target = target.wrapping_add(base);
+                }
+                let high8 = (raw >> 36) & 0xff;
+                Some((next, ChainedPtr::Rebase(target | (high8 << 56), None)))
+            }
+        }
+        types::DYLD_CHAINED_PTR_ARM64E | types::DYLD_CHAINED_PTR_ARM64E_USERLAND |
+        types::DYLD_CHAINED_PTR_ARM64E_USERLAND24 => {
+            let next = (raw >> 51) & 0x7ff;
+            let is_auth = raw >> 63 != 0;
+            let is_bind = (raw >> 62) & 1 != 0;
+            let auth = if is_auth {
+                Some(types::PtrAuth {
+                    diversity: ((raw >> 32) & 0xffff) as u16,
+                    addr_div: (raw >> 48) & 1 != 0,
+                    key: ((raw >> 49) & 3) as u8,
+                })
+            } else {
+                None
+            };
+            if is_bind {
+                let ordinal = if format == types::DYLD_CHAINED_PTR_ARM64E_USERLAND24 {
+                    (raw & 0xffffff) as u32
+                } else {
+                    (raw & 0xffff) as u32
+                };
+                let addend = if is_auth {
+                    0
+                } else {
+                    sign_extend((raw >> 32) & 0x7ffff, 19)
+                };
+                Some((next, ChainedPtr::Bind(ordinal, addend, auth)))
+            } else if is_auth {
+                // Authenticated rebase targets are always image offsets
+                Some((next, ChainedPtr::Rebase(base.wrapping_add(raw & 0xffffffff), auth)))
+            } else {
+                let mut target = raw & 0x7ff_ffff_ffff;
+                if format != types::DYLD_CHAINED_PTR_ARM64E {
+                    target = target.wrapping_add(base);
+                }
+                let high8 = (raw >> 43) & 0xff;
+                Some((next, ChainedPtr::Rebase(target | (high8 << 56), None)))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Stride in bytes of the `next` field of a chained pointer format
+fn ptr_stride(format: u16) -> Option<u64> {
+    match format {
+        types::DYLD_CHAINED_PTR_ARM64E | types::DYLD_CHAINED_PTR_ARM64E_USERLAND |
+        types::DYLD_CHAINED_PTR_ARM64E_USERLAND24 => Some(8),
+        types::DYLD_CHAINED_PTR_64 | types::DYLD_CHAINED_PTR_64_OFFSET => Some(4),
+        _ => None,
+    }
+}
+
+/// Parse the LC_DYLD_CHAINED_FIXUPS payload `blob` and walk the fixup chains
+/// in the segments read from `r`.
+///
+/// Only the 64-bit userland pointer formats (DYLD_CHAINED_PTR_64,
+/// DYLD_CHAINED_PTR_64_OFFSET and the ARM64E variants) are walked; segments
+/// using other formats are listed in `starts` but produce no records.
+pub fn parse_chained_fixups<R: io::Read + io::Seek>(r: &mut R, blob: &[u8], segments: &[types::SegmentCommand],
+                                                    dylibs: &[types::Dylib], base: u64)
+                                                    -> Result<types::ChainedFixups, Box<error::Error>> {
+    let mut c = io::Cursor::new(blob);
+    let hdr = types::ChainedFixupsHeader {
+        fixups_version: try!(c.read_u32::<byteorder::LittleEndian>()),
+        starts_offset: try!(c.read_u32::<byteorder::LittleEndian>()),
+        imports_offset: try!(c.read_u32::<byteorder::LittleEndian>()),
+        symbols_offset: try!(c.read_u32::<byteorder::LittleEndian>()),
+        imports_count: try!(c.read_u32::<byteorder::LittleEndian>()),
+        imports_format: try!(c.read_u32::<byteorder::LittleEndian>()),
+        symbols_format: try!(c.read_u32::<byteorder::LittleEndian>()),
+    };
+    let imports = try!(read_imports(blob, &hdr, dylibs));
+    let starts = try!(read_starts(blob, &hdr));
+
+    let mut rebases = Vec::new();
+    let mut binds = Vec::new();
+    for seg_starts in starts.iter() {
+        let stride = match ptr_stride(seg_starts.pointer_format) {
+            Some(x) => x,
+            None => continue,
+        };
+        let seg = match segments.get(seg_starts.seg_index as usize) {
+            Some(x) => x,
+            None => return Err(Box::new(Error::from("invalid chained fixups segment index"))),
+        };
+        try!(r.seek(io::SeekFrom::Start(seg.file_off)));
+        let mut seg_data = Vec::new();
+        try!(io::Read::by_ref(r).take(seg.filesize).read_to_end(&mut seg_data));
+
+        for (page, start) in seg_starts.page_starts.iter().enumerate() {
+            if *start == types::DYLD_CHAINED_PTR_START_NONE {
+                continue;
+            }
+            let mut off = page as u64 * seg_starts.page_size as u64 + *start as u64;
+            loop {
+                let raw = match seg_data.get(off as usize..off as usize + 8) {
+                    Some(mut x) => try!(x.read_u64::<byteorder::LittleEndian>()),
+                    None => return Err(Box::new(Error::from("fixup chain out of bounds"))),
+                };
+                let (next, ptr) = match decode_ptr(seg_starts.pointer_format, raw, base) {
+                    Some(x) => x,
+                    None => break,
+                };
+                match ptr {
+                    ChainedPtr::Rebase(target, auth) => {
+                        rebases.push(types::ChainedRebase {
+                            seg_index: seg_starts.seg_index,
+                            seg_offset: off,
+                            address: seg.vm_addr.wrapping_add(off),
+                            target: target,
+                            auth: auth,
+                        });
+                    }
+                    ChainedPtr::Bind(ordinal, addend, auth) => {
+                        let import = match imports.get(ordinal as usize) {
+                            Some(x) => x,
+                            None => return Err(Box::new(Error::from("invalid chained import ordinal"))),
+                        };
+                        binds.push(types::ChainedBind {
+                            seg_index: seg_starts.seg_index,
+                            seg_offset: off,
+                            address: seg.vm_addr.wrapping_add(off),
+                            import: ordinal,
+                            symbol_name: import.name.clone(),
+                            library_ordinal: import.library_ordinal,
+                            library: import.library.clone(),
+                            weak_import: import.weak_import,
+                            addend: import.addend.wrapping_add(addend),
+                            auth: auth,
+                        });
+                    }
+                }
+                if next == 0 {
+                    break;
+                }
+                off += next * stride;
+            }
+        }
+    }
+
+    Ok(types::ChainedFixups {
+        header: hdr,
+        imports: imports,
+        starts: starts,
+        rebases: rebases,
+        binds: binds,
+    })
+}
@@ -1,4 +1,11 @@
 pub mod file;
+pub mod codesign;
+pub mod dyld;
+pub mod fat;
+pub mod fixups;
+pub mod objc;
+pub mod reloc;
 pub mod types;
 
 pub use mach::file::File;
+pub use mach::fat::FatFile;
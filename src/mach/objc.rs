@@ -0,0 +1,386 @@
+use std::io;
+use std::error;
+use std::collections::HashMap;
+use byteorder;
+use byteorder::ReadBytesExt;
+use mach::types;
+use mach::File;
+use Error;
+
+/// Target of a pointer stored in the image
+enum Pointer {
+    Address(u64),
+    /// Bound to a symbol in another image
+    Symbol(String),
+    Null,
+}
+
+/// Virtual memory view of a Mach-O image built from its section data, with
+/// rebases and binds applied to pointer reads
+struct Image<'a> {
+    sections: Vec<(u64, &'a [u8])>,
+    data: types::Data,
+    ptr_size: u64,
+    rebases: HashMap<u64, u64>,
+    binds: HashMap<u64, &'a str>,
+}
+
+impl<'a> Image<'a> {
+    fn new(file: &'a File) -> Image<'a> {
+        let hdr = file.header();
+        let mut rebases = HashMap::new();
+        let mut binds = HashMap::new();
+        for bind in file.binds().iter() {
+            binds.insert(bind.address, bind.symbol_name.as_str());
+        }
+        if let Some(fixups) = file.chained_fixups() {
+            for rebase in fixups.rebases.iter() {
+                rebases.insert(rebase.address, rebase.target);
+            }
+            for bind in fixups.binds.iter() {
+                binds.insert(bind.address, bind.symbol_name.as_str());
+            }
+        }
+        Image {
+            sections: file.sections().iter().map(|x| (x.addr(), x.data())).collect(),
+            data: hdr.data,
+            ptr_size: if hdr.class == types::MACH_CLASS_32 { 4 } else { 8 },
+            rebases: rebases,
+            binds: binds,
+        }
+    }
+
+    fn bytes(&self, addr: u64, len: u64) -> Result<&'a [u8], Box<error::Error>> {
+        for &(start, data) in self.sections.iter() {
+            if addr >= start && addr - start < data.len() as u64 {
+                let off = (addr - start) as usize;
+                if let Some(x) = off.checked_add(len as usize).and_then(|end| data.get(off..end)) {
+                    return Ok(x);
+                }
+            }
+        }
+        Err(Box::new(Error::from("address not mapped by any section")))
+    }
+
+    /// Number of bytes from `addr` to the end of the section holding it
+    fn bytes_left(&self, addr: u64) -> u64 {
+        for &(start, data) in self.sections.iter() {
+            if addr >= start && addr - start < data.len() as u64 {
+                return data.len() as u64 - (addr - start);
+            }
+        }
+        0
+    }
+
+    /// Entry count of the method, ivar or property list at `addr`, checked
+    /// against the section holding it so a corrupt header cannot run away.
+    /// `min_entsize` is the size of the fields read from each entry.
+    fn list_count(&self, addr: u64, entsize: u64, min_entsize: u64) -> Result<u64, Box<error::Error>> {
+        if entsize < min_entsize {
+            return Err(Box::new(Error::from("invalid list entry size")));
+        }
+        let count = try!(self.u32(addr.wrapping_add(4))) as u64;
+        if count > self.bytes_left(addr.wrapping_add(8)) / entsize {
+            return Err(Box::new(Error::from("list extends past its section")));
+        }
+        Ok(count)
+    }
+
+    fn u32(&self, addr: u64) -> Result<u32, Box<error::Error>> {
+        let mut c = io::Cursor::new(try!(self.bytes(addr, 4)));
+        match self.data {
+            types::MACH_DATA_2LSB => Ok(try!(c.read_u32::<byteorder::LittleEndian>())),
+            _ => Ok(try!(c.read_u32::<byteorder::BigEndian>())),
+        }
+    }
+
+    fn i32(&self, addr: u64) -> Result<i32, Box<error::Error>> {
+        self.u32(addr).map(|x| x as i32)
+    }
+
+    fn raw_ptr(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        if self.ptr_size == 4 {
+            return self.u32(addr).map(|x| x as u64);
+        }
+        let mut c = io::Cursor::new(try!(self.bytes(addr, 8)));
+        match self.data {
+            types::MACH_DATA_2LSB => Ok(try!(c.read_u64::<byteorder::LittleEndian>())),
+            _ => Ok(try!(c.read_u64::<byteorder::BigEndian>())),
+        }
+    }
+
+    fn ptr(&self, addr: u64) -> Result<Pointer, Box<error::Error>> {
+        if let Some(name) = self.binds.get(&addr) {
+            return Ok(Pointer::Symbol(String::from(*name)));
+        }
+        let value = match self.rebases.get(&addr) {
+            // Drop the top byte carried by arm64e and high8 targets
+            Some(x) => x & 0x00ffffffffffffff,
+            None => try!(self.raw_ptr(addr)),
+        };
+        if value == 0 {
+            Ok(Pointer::Null)
+        } else {
+            Ok(Pointer::Address(value))
+        }
+    }
+
+    /// Pointer that must refer to this image, 0 if it is null or bound
+    fn addr(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        match try!(self.ptr(addr)) {
+            Pointer::Address(x) => Ok(x),
+            _ => Ok(0),
+        }
+    }
+
+    fn string(&self, addr: u64) -> Result<String, Box<error::Error>> {
+        for &(start, data) in self.sections.iter() {
+            if addr >= start && addr - start < data.len() as u64 {
+                let tail = &data[(addr - start) as usize..];
+                let end = tail.iter().position(|x| *x == 0).unwrap_or(tail.len());
+                return Ok(String::from_utf8_lossy(&tail[..end]).into_owned());
+            }
+        }
+        Err(Box::new(Error::from("string not mapped by any section")))
+    }
+
+    fn string_ptr(&self, addr: u64) -> Result<String, Box<error::Error>> {
+        match try!(self.addr(addr)) {
+            0 => Ok(String::new()),
+            x => self.string(x),
+        }
+    }
+
+    /// Pointer list section such as __objc_classlist
+    fn list(&self, sect: &::Section) -> Result<Vec<u64>, Box<error::Error>> {
+        let mut ret = Vec::new();
+        for i in 0..sect.data().len() as u64 / self.ptr_size {
+            ret.push(try!(self.addr(sect.addr().wrapping_add(i * self.ptr_size))));
+        }
+        Ok(ret)
+    }
+
+    fn methods(&self, addr: u64) -> Result<Vec<types::ObjcMethod>, Box<error::Error>> {
+        let mut ret = Vec::new();
+        if addr == 0 {
+            return Ok(ret);
+        }
+        let entsize_flags = try!(self.u32(addr));
+        let entsize = (entsize_flags & 0xfffc) as u64;
+        let relative = entsize_flags & types::METHOD_LIST_IS_RELATIVE != 0;
+        let count = try!(self.list_count(addr, entsize, if relative { 12 } else { 3 * self.ptr_size }));
+        for i in 0..count {
+            let m = addr.wrapping_add(8 + i * entsize);
+            if relative {
+                // Offsets are relative to each field; the name refers to a
+                // selector reference rather than the string itself
+                let rel = |field: u64| -> Result<u64, Box<error::Error>> {
+                    let off = try!(self.i32(m.wrapping_add(field)));
+                    Ok(m.wrapping_add(field).wrapping_add(off as i64 as u64))
+                };
+                let imp = try!(self.i32(m.wrapping_add(8)));
+                ret.push(types::ObjcMethod {
+                    name: try!(self.string_ptr(try!(rel(0)))),
+                    types: try!(self.string(try!(rel(4)))),
+                    imp: if imp == 0 { 0 } else { try!(rel(8)) },
+                });
+            } else {
+                ret.push(types::ObjcMethod {
+                    name: try!(self.string_ptr(m)),
+                    types: try!(self.string_ptr(m.wrapping_add(self.ptr_size))),
+                    imp: try!(self.addr(m.wrapping_add(2 * self.ptr_size))),
+                });
+            }
+        }
+        Ok(ret)
+    }
+
+    fn ivars(&self, addr: u64) -> Result<Vec<types::ObjcIvar>, Box<error::Error>> {
+        let mut ret = Vec::new();
+        if addr == 0 {
+            return Ok(ret);
+        }
+        let entsize = try!(self.u32(addr)) as u64;
+        // offset, name and type pointers, alignment and size
+        let count = try!(self.list_count(addr, entsize, 3 * self.ptr_size + 8));
+        for i in 0..count {
+            let v = addr.wrapping_add(8 + i * entsize);
+            let offset_addr = try!(self.addr(v));
+            ret.push(types::ObjcIvar {
+                offset: if offset_addr == 0 { 0 } else { try!(self.u32(offset_addr)) },
+                name: try!(self.string_ptr(v.wrapping_add(self.ptr_size))),
+                type_encoding: try!(self.string_ptr(v.wrapping_add(2 * self.ptr_size))),
+                size: try!(self.u32(v.wrapping_add(3 * self.ptr_size + 4))),
+            });
+        }
+        Ok(ret)
+    }
+
+    fn properties(&self, addr: u64) -> Result<Vec<types::ObjcProperty>, Box<error::Error>> {
+        let mut ret = Vec::new();
+        if addr == 0 {
+            return Ok(ret);
+        }
+        let entsize = try!(self.u32(addr)) as u64;
+        let count = try!(self.list_count(addr, entsize, 2 * self.ptr_size));
+        for i in 0..count {
+            let p = addr.wrapping_add(8 + i * entsize);
+            ret.push(types::ObjcProperty {
+                name: try!(self.string_ptr(p)),
+                attributes: try!(self.string_ptr(p.wrapping_add(self.ptr_size))),
+            });
+        }
+        Ok(ret)
+    }
+
+    /// Names of the protocols in a protocol_list_t
+    fn protocol_names(&self, addr: u64) -> Result<Vec<String>, Box<error::Error>> {
+        let mut ret = Vec::new();
+        if addr == 0 {
+            return Ok(ret);
+        }
+        let count = try!(self.raw_ptr(addr));
+        if count > self.bytes_left(addr.wrapping_add(self.ptr_size)) / self.ptr_size {
+            return Err(Box::new(Error::from("list extends past its section")));
+        }
+        for i in 0..count {
+            let entry = addr.wrapping_add((i + 1) * self.ptr_size);
+            match try!(self.ptr(entry)) {
+                Pointer::Address(x) => ret.push(try!(self.string_ptr(x.wrapping_add(self.ptr_size)))),
+                Pointer::Symbol(x) => ret.push(strip_prefix(&x, "__OBJC_PROTOCOL_$_")),
+                Pointer::Null => {}
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Address of the class_ro_t of the class_t at `addr`
+    fn class_ro(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        // The low bits of the data pointer hold runtime and Swift flags
+        let mask = if self.ptr_size == 4 { !3 } else { !7 };
+        Ok(try!(self.addr(addr.wrapping_add(4 * self.ptr_size))) & mask)
+    }
+
+    /// Fields of a class_ro_t that follow the flags and sizes: the name,
+    /// method, protocol, ivar and property list addresses
+    fn ro_fields(&self, ro: u64) -> Result<(String, u64, u64, u64, u64), Box<error::Error>> {
+        // 64-bit class_ro_t has a reserved word after instanceSize
+        let base = ro.wrapping_add(if self.ptr_size == 4 { 12 } else { 16 } + self.ptr_size);
+        Ok((try!(self.string_ptr(base)),
+            try!(self.addr(base.wrapping_add(self.ptr_size))),
+            try!(self.addr(base.wrapping_add(2 * self.ptr_size))),
+            try!(self.addr(base.wrapping_add(3 * self.ptr_size))),
+            try!(self.addr(base.wrapping_add(5 * self.ptr_size)))))
+    }
+
+    fn class(&self, addr: u64) -> Result<types::ObjcClass, Box<error::Error>> {
+        let ro = try!(self.class_ro(addr));
+        let flags = try!(self.u32(ro));
+        let instance_size = try!(self.u32(ro.wrapping_add(8)));
+        let (name, methods, protocols, ivars, properties) = try!(self.ro_fields(ro));
+
+        let mut class_methods = Vec::new();
+        let isa = try!(self.addr(addr));
+        if isa != 0 {
+            let meta_ro = try!(self.class_ro(isa));
+            class_methods = try!(self.methods(try!(self.ro_fields(meta_ro)).1));
+        }
+
+        Ok(types::ObjcClass {
+            address: addr,
+            name: name,
+            superclass: try!(self.class_name(addr.wrapping_add(self.ptr_size))),
+            flags: flags,
+            instance_size: instance_size,
+            instance_methods: try!(self.methods(methods)),
+            class_methods: class_methods,
+            ivars: try!(self.ivars(ivars)),
+            protocols: try!(self.protocol_names(protocols)),
+            properties: try!(self.properties(properties)),
+        })
+    }
+
+    /// Name of the class a class_t pointer at `addr` refers to
+    fn class_name(&self, addr: u64) -> Result<Option<String>, Box<error::Error>> {
+        match try!(self.ptr(addr)) {
+            Pointer::Address(x) => {
+                let ro = try!(self.class_ro(x));
+                Ok(Some(try!(self.ro_fields(ro)).0))
+            }
+            Pointer::Symbol(x) => Ok(Some(strip_prefix(&x, "_OBJC_CLASS_$_"))),
+            Pointer::Null => Ok(None),
+        }
+    }
+
+    fn category(&self, addr: u64) -> Result<types::ObjcCategory, Box<error::Error>> {
+        let p = self.ptr_size;
+        Ok(types::ObjcCategory {
+            address: addr,
+            name: try!(self.string_ptr(addr)),
+            class: try!(self.class_name(addr.wrapping_add(p))),
+            instance_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(2 * p))))),
+            class_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(3 * p))))),
+            protocols: try!(self.protocol_names(try!(self.addr(addr.wrapping_add(4 * p))))),
+            properties: try!(self.properties(try!(self.addr(addr.wrapping_add(5 * p))))),
+        })
+    }
+
+    fn protocol(&self, addr: u64) -> Result<types::ObjcProtocol, Box<error::Error>> {
+        let p = self.ptr_size;
+        Ok(types::ObjcProtocol {
+            address: addr,
+            name: try!(self.string_ptr(addr.wrapping_add(p))),
+            protocols: try!(self.protocol_names(try!(self.addr(addr.wrapping_add(2 * p))))),
+            instance_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(3 * p))))),
+            class_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(4 * p))))),
+            optional_instance_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(5 * p))))),
+            optional_class_methods: try!(self.methods(try!(self.addr(addr.wrapping_add(6 * p))))),
+            properties: try!(self.properties(try!(self.addr(addr.wrapping_add(7 * p))))),
+        })
+    }
+}
+
+fn strip_prefix(name: &str, prefix: &str) -> String {
+    String::from(name.trim_start_matches(prefix))
+}
+
+/// Walk the Objective-C class, category, protocol and selector reference
+/// lists of `file`
+pub fn parse_objc(file: &File) -> Result<types::ObjcMetadata, Box<error::Error>> {
+    let image = Image::new(file);
+    let mut ret = types::ObjcMetadata {
+        classes: Vec::new(),
+        categories: Vec::new(),
+        protocols: Vec::new(),
+        selectors: Vec::new(),
+    };
+    for sect in file.sections().iter().filter(|x| x.name() == "__objc_classlist") {
+        for addr in try!(image.list(sect)) {
+            if addr != 0 {
+                ret.classes.push(try!(image.class(addr)));
+            }
+        }
+    }
+    for sect in file.sections().iter().filter(|x| x.name() == "__objc_catlist") {
+        for addr in try!(image.list(sect)) {
+            if addr != 0 {
+                ret.categories.push(try!(image.category(addr)));
+            }
+        }
+    }
+    for sect in file.sections().iter().filter(|x| x.name() == "__objc_protolist") {
+        for addr in try!(image.list(sect)) {
+            if addr != 0 {
+                ret.protocols.push(try!(image.protocol(addr)));
+            }
+        }
+    }
+    for sect in file.sections().iter().filter(|x| x.name() == "__objc_selrefs") {
+        for addr in try!(image.list(sect)) {
+            if addr != 0 {
+                ret.selectors.push(try!(image.string(addr)));
+            }
+        }
+    }
+    Ok(ret)
+}
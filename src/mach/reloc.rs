@@ -0,0 +1,82 @@
+use std::io;
+use std::error;
+use byteorder;
+use byteorder::ReadBytesExt;
+use mach::types;
+use Error;
+
+fn read_word(data: types::Data, c: &mut io::Cursor<&[u8]>) -> Result<u32, Box<error::Error>> {
+    match data {
+        types::MACH_DATA_2LSB => Ok(try!(c.read_u32::<byteorder::LittleEndian>())),
+        types::MACH_DATA_2MSB => Ok(try!(c.read_u32::<byteorder::BigEndian>())),
+        _ => Err(Box::new(Error::from("invalid endianness"))),
+    }
+}
+
+/// Decode the `nreloc` relocation entries of a section. ARM64_RELOC_ADDEND
+/// entries are folded into the relocation that follows them.
+pub fn parse_relocations(blob: &[u8], data: types::Data, cpu_type: types::Machine,
+                         symtab: &[types::Symbol]) -> Result<Vec<types::Relocation>, Box<error::Error>> {
+    // x86_64 and ARM64 never use scattered relocations, so the high bit of
+    // r_address carries no meaning there
+    let has_scattered = cpu_type != types::PM_X86_64 && cpu_type != types::PM_ARM64 &&
+                        cpu_type != types::PM_ARM64_32;
+    let mut c = io::Cursor::new(blob);
+    let mut ret = Vec::new();
+    let mut addend = None;
+    for _ in 0..blob.len() / 8 {
+        let word0 = try!(read_word(data, &mut c));
+        let word1 = try!(read_word(data, &mut c));
+
+        if has_scattered && word0 & types::R_SCATTERED != 0 {
+            let r_type = ((word0 >> 24) & 0xf) as u8;
+            ret.push(types::Relocation {
+                address: word0 & 0x00ffffff,
+                symbolnum: 0,
+                pcrel: word0 & 0x40000000 != 0,
+                length: ((word0 >> 28) & 0x3) as u8,
+                is_extern: false,
+                r_type: r_type,
+                kind: types::RelocationKind::new(cpu_type, r_type),
+                scattered: true,
+                value: word1,
+                addend: 0,
+                symbol: None,
+            });
+            continue;
+        }
+
+        // The bitfields are laid out from the opposite end of the word in
+        // big endian files
+        let (symbolnum, pcrel, length, is_extern, r_type) = if data == types::MACH_DATA_2MSB {
+            (word1 >> 8, (word1 >> 7) & 1, (word1 >> 5) & 3, (word1 >> 4) & 1, word1 & 0xf)
+        } else {
+            (word1 & 0x00ffffff, (word1 >> 24) & 1, (word1 >> 25) & 3, (word1 >> 27) & 1, word1 >> 28)
+        };
+        let kind = types::RelocationKind::new(cpu_type, r_type as u8);
+        if kind == types::RelocationKind::Arm64(types::Arm64Reloc::Addend) {
+            // The 24-bit addend is stored sign extended in r_symbolnum
+            addend = Some((((symbolnum << 8) as i32) >> 8) as i64);
+            continue;
+        }
+        let symbol = if is_extern != 0 {
+            symtab.get(symbolnum as usize).map(|x| x.name.clone())
+        } else {
+            None
+        };
+        ret.push(types::Relocation {
+            address: word0,
+            symbolnum: symbolnum,
+            pcrel: pcrel != 0,
+            length: length as u8,
+            is_extern: is_extern != 0,
+            r_type: r_type as u8,
+            kind: kind,
+            scattered: false,
+            value: 0,
+            addend: addend.take().unwrap_or(0),
+            symbol: symbol,
+        });
+    }
+    Ok(ret)
+}
@@ -1,6 +1,6 @@
 use std::fmt;
-use libc::c_ulong;
 
+#[derive(Debug)]
 pub struct LoadCommand {
     pub cmd: u32,
     pub size: u32,
@@ -8,7 +8,10 @@ pub struct LoadCommand {
 
 pub struct FileHeader {
     pub magic: u32,
+    pub class: Class,
     pub cpu_type: Machine,
+    /// CPU subtype with the CPU_SUBTYPE_LIB64 bit inverted; see
+    /// `raw_cpu_subtype` for the value stored in the file
     pub cpu_subtype: u32,
     pub file_type: u32,
     pub ncmds: u32,
@@ -17,32 +20,452 @@ pub struct FileHeader {
     pub data: Data,
 }
 
+impl FileHeader {
+    /// CPU subtype as stored in the header, including the capability bits
+    pub fn raw_cpu_subtype(&self) -> u32 {
+        self.cpu_subtype ^ CPU_SUBTYPE_LIB64
+    }
+    /// CPU subtype without the capability bits
+    pub fn subtype(&self) -> u32 {
+        self.raw_cpu_subtype() & !CPU_SUBTYPE_MASK
+    }
+    pub fn ptrauth_version(&self) -> Option<u32> {
+        ptrauth_version(self.cpu_type, self.raw_cpu_subtype())
+    }
+}
+
 #[derive(Debug)]
+pub struct SegmentCommand {
+    pub seg_name: String,
+    pub vm_addr: u64,
+    pub vm_size: u64,
+    pub file_off: u64,
+    pub filesize: u64,
+    pub maxprot: i32,
+    pub initprot: i32,
+    pub flags: u32,
+    pub sections: Vec<SectionHeader>,
+}
+
+impl SegmentCommand {
+    pub fn is_readable(&self) -> bool {
+        self.initprot & VM_PROT_READ != 0
+    }
+    pub fn is_writable(&self) -> bool {
+        self.initprot & VM_PROT_WRITE != 0
+    }
+    pub fn is_executable(&self) -> bool {
+        self.initprot & VM_PROT_EXECUTE != 0
+    }
+}
+
+/// Segment is readable
+pub const VM_PROT_READ: i32 = 0x1;
+/// Segment is writable
+pub const VM_PROT_WRITE: i32 = 0x2;
+/// Segment is executable
+pub const VM_PROT_EXECUTE: i32 = 0x4;
+
+/// Segment occupies the high part of the VM space
+pub const SG_HIGHVM: u32 = 0x1;
+/// Segment is allocated by a fixed VM library
+pub const SG_FVMLIB: u32 = 0x2;
+/// Segment has nothing relocated in it and nothing relocated to it
+pub const SG_NORELOC: u32 = 0x4;
+/// Segment is protected; the first page is not
+pub const SG_PROTECTED_VERSION_1: u32 = 0x8;
+/// Segment is made read-only after fixups
+pub const SG_READ_ONLY: u32 = 0x10;
+
+#[derive(Clone, Debug)]
 pub struct SectionHeader {
     pub sect_name: String,
     pub seg_name: String,
-    pub addr: c_ulong,
-    pub size: c_ulong,
+    pub addr: u64,
+    pub size: u64,
     pub offset: u32,
     pub align: u32,
     pub reloff: u32,
     pub nreloc: u32,
     pub flags: u32,
+    pub reserved1: u32,
+    pub reserved2: u32,
 }
 
+/// Mask for the section type bits of the section flags
+pub const SECTION_TYPE: u32 = 0x000000ff;
+/// Mask for the section attribute bits of the section flags
+pub const SECTION_ATTRIBUTES: u32 = 0xffffff00;
+
+/// Regular section
+pub const S_REGULAR: u32 = 0x0;
+/// Zero-fill on demand section
+pub const S_ZEROFILL: u32 = 0x1;
+/// Section with only literal C strings
+pub const S_CSTRING_LITERALS: u32 = 0x2;
+/// Section with only 4 byte literals
+pub const S_4BYTE_LITERALS: u32 = 0x3;
+/// Section with only 8 byte literals
+pub const S_8BYTE_LITERALS: u32 = 0x4;
+/// Section with only pointers to literals
+pub const S_LITERAL_POINTERS: u32 = 0x5;
+/// Section with only non-lazy symbol pointers
+pub const S_NON_LAZY_SYMBOL_POINTERS: u32 = 0x6;
+/// Section with only lazy symbol pointers
+pub const S_LAZY_SYMBOL_POINTERS: u32 = 0x7;
+/// Section with only symbol stubs, stub size in reserved2
+pub const S_SYMBOL_STUBS: u32 = 0x8;
+/// Section with only function pointers for initialization
+pub const S_MOD_INIT_FUNC_POINTERS: u32 = 0x9;
+/// Section with only function pointers for termination
+pub const S_MOD_TERM_FUNC_POINTERS: u32 = 0xa;
+/// Section with only symbols that are to be coalesced
+pub const S_COALESCED: u32 = 0xb;
+/// Zero-fill on demand section that can be larger than 4 gigabytes
+pub const S_GB_ZEROFILL: u32 = 0xc;
+/// Section with only pairs of function pointers for interposing
+pub const S_INTERPOSING: u32 = 0xd;
+/// Section with only 16 byte literals
+pub const S_16BYTE_LITERALS: u32 = 0xe;
+/// Section containing DTrace Object Format
+pub const S_DTRACE_DOF: u32 = 0xf;
+/// Section with only lazy symbol pointers to lazy loaded dylibs
+pub const S_LAZY_DYLIB_SYMBOL_POINTERS: u32 = 0x10;
+/// Template of initial values for thread-local variables
+pub const S_THREAD_LOCAL_REGULAR: u32 = 0x11;
+/// Template of initial zero-fill thread-local variables
+pub const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+/// Thread-local variable descriptors
+pub const S_THREAD_LOCAL_VARIABLES: u32 = 0x13;
+/// Pointers to thread-local variable descriptors
+pub const S_THREAD_LOCAL_VARIABLE_POINTERS: u32 = 0x14;
+/// Functions to call to initialize thread-local variable values
+pub const S_THREAD_LOCAL_INIT_FUNCTION_POINTERS: u32 = 0x15;
+/// 32-bit offsets to initializers
+pub const S_INIT_FUNC_OFFSETS: u32 = 0x16;
+
+/// Section contains only true machine instructions
+pub const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x80000000;
+/// Section contents are not to be in a ranlib table of contents
+pub const S_ATTR_NO_TOC: u32 = 0x40000000;
+/// Symbols in this section may be stripped from files with MH_DYLDLINK
+pub const S_ATTR_STRIP_STATIC_SYMS: u32 = 0x20000000;
+/// Section must not be dead-stripped
+pub const S_ATTR_NO_DEAD_STRIP: u32 = 0x10000000;
+/// Blocks are live if they reference live blocks
+pub const S_ATTR_LIVE_SUPPORT: u32 = 0x08000000;
+/// Used with i386 code stubs written on by dyld
+pub const S_ATTR_SELF_MODIFYING_CODE: u32 = 0x04000000;
+/// Debug section
+pub const S_ATTR_DEBUG: u32 = 0x02000000;
+/// Section contains some machine instructions
+pub const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x00000400;
+/// Section has external relocation entries
+pub const S_ATTR_EXT_RELOC: u32 = 0x00000200;
+/// Section has local relocation entries
+pub const S_ATTR_LOC_RELOC: u32 = 0x00000100;
+
 impl SectionHeader {
     pub fn address(&self) -> u64 {
-        self.addr as u64
+        self.addr
+    }
+    /// Section type bits of the flags (S_REGULAR, S_ZEROFILL, ...)
+    pub fn sect_type(&self) -> u32 {
+        self.flags & SECTION_TYPE
+    }
+}
+
+/// relocation_info r_address flag marking a scattered_relocation_info
+pub const R_SCATTERED: u32 = 0x80000000;
+/// relocation_info r_symbolnum of a non-extern relocation to an absolute address
+pub const R_ABS: u32 = 0;
+
+pub const GENERIC_RELOC_VANILLA: u8 = 0;
+pub const GENERIC_RELOC_PAIR: u8 = 1;
+pub const GENERIC_RELOC_SECTDIFF: u8 = 2;
+pub const GENERIC_RELOC_PB_LA_PTR: u8 = 3;
+pub const GENERIC_RELOC_LOCAL_SECTDIFF: u8 = 4;
+pub const GENERIC_RELOC_TLV: u8 = 5;
+
+pub const ARM_RELOC_VANILLA: u8 = 0;
+pub const ARM_RELOC_PAIR: u8 = 1;
+pub const ARM_RELOC_SECTDIFF: u8 = 2;
+pub const ARM_RELOC_LOCAL_SECTDIFF: u8 = 3;
+pub const ARM_RELOC_PB_LA_PTR: u8 = 4;
+pub const ARM_RELOC_BR24: u8 = 5;
+pub const ARM_THUMB_RELOC_BR22: u8 = 6;
+pub const ARM_THUMB_32BIT_BRANCH: u8 = 7;
+pub const ARM_RELOC_HALF: u8 = 8;
+pub const ARM_RELOC_HALF_SECTDIFF: u8 = 9;
+
+pub const X86_64_RELOC_UNSIGNED: u8 = 0;
+pub const X86_64_RELOC_SIGNED: u8 = 1;
+pub const X86_64_RELOC_BRANCH: u8 = 2;
+pub const X86_64_RELOC_GOT_LOAD: u8 = 3;
+pub const X86_64_RELOC_GOT: u8 = 4;
+pub const X86_64_RELOC_SUBTRACTOR: u8 = 5;
+pub const X86_64_RELOC_SIGNED_1: u8 = 6;
+pub const X86_64_RELOC_SIGNED_2: u8 = 7;
+pub const X86_64_RELOC_SIGNED_4: u8 = 8;
+pub const X86_64_RELOC_TLV: u8 = 9;
+
+pub const ARM64_RELOC_UNSIGNED: u8 = 0;
+pub const ARM64_RELOC_SUBTRACTOR: u8 = 1;
+pub const ARM64_RELOC_BRANCH26: u8 = 2;
+pub const ARM64_RELOC_PAGE21: u8 = 3;
+pub const ARM64_RELOC_PAGEOFF12: u8 = 4;
+pub const ARM64_RELOC_GOT_LOAD_PAGE21: u8 = 5;
+pub const ARM64_RELOC_GOT_LOAD_PAGEOFF12: u8 = 6;
+pub const ARM64_RELOC_POINTER_TO_GOT: u8 = 7;
+pub const ARM64_RELOC_TLVP_LOAD_PAGE21: u8 = 8;
+pub const ARM64_RELOC_TLVP_LOAD_PAGEOFF12: u8 = 9;
+pub const ARM64_RELOC_ADDEND: u8 = 10;
+pub const ARM64_RELOC_AUTHENTICATED_POINTER: u8 = 11;
+
+/// x86_64 relocation type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum X86_64Reloc {
+    Unsigned,
+    Signed,
+    Branch,
+    GotLoad,
+    Got,
+    /// Must be followed by an Unsigned relocation; the pair computes the
+    /// difference of the two symbols
+    Subtractor,
+    Signed1,
+    Signed2,
+    Signed4,
+    Tlv,
+}
+
+/// ARM64 relocation type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arm64Reloc {
+    Unsigned,
+    /// Must be followed by an Unsigned relocation; the pair computes the
+    /// difference of the two symbols
+    Subtractor,
+    Branch26,
+    Page21,
+    PageOff12,
+    GotLoadPage21,
+    GotLoadPageOff12,
+    PointerToGot,
+    TlvpLoadPage21,
+    TlvpLoadPageOff12,
+    /// Addend for the following relocation; folded into it when parsing
+    Addend,
+    AuthenticatedPointer,
+}
+
+/// Relocation type, decoded according to the CPU type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RelocationKind {
+    X86_64(X86_64Reloc),
+    Arm64(Arm64Reloc),
+    /// i386 (GENERIC_RELOC_*), ARM (ARM_RELOC_*) or unknown relocation type
+    Other(u8),
+}
+
+impl RelocationKind {
+    pub fn new(cpu_type: Machine, r_type: u8) -> RelocationKind {
+        match cpu_type {
+            PM_X86_64 => match r_type {
+                X86_64_RELOC_UNSIGNED => RelocationKind::X86_64(X86_64Reloc::Unsigned),
+                X86_64_RELOC_SIGNED => RelocationKind::X86_64(X86_64Reloc::Signed),
+                X86_64_RELOC_BRANCH => RelocationKind::X86_64(X86_64Reloc::Branch),
+                X86_64_RELOC_GOT_LOAD => RelocationKind::X86_64(X86_64Reloc::GotLoad),
+                X86_64_RELOC_GOT => RelocationKind::X86_64(X86_64Reloc::Got),
+                X86_64_RELOC_SUBTRACTOR => RelocationKind::X86_64(X86_64Reloc::Subtractor),
+                X86_64_RELOC_SIGNED_1 => RelocationKind::X86_64(X86_64Reloc::Signed1),
+                X86_64_RELOC_SIGNED_2 => RelocationKind::X86_64(X86_64Reloc::Signed2),
+                X86_64_RELOC_SIGNED_4 => RelocationKind::X86_64(X86_64Reloc::Signed4),
+                X86_64_RELOC_TLV => RelocationKind::X86_64(X86_64Reloc::Tlv),
+                _ => RelocationKind::Other(r_type),
+            },
+            PM_ARM64 | PM_ARM64_32 => match r_type {
+                ARM64_RELOC_UNSIGNED => RelocationKind::Arm64(Arm64Reloc::Unsigned),
+                ARM64_RELOC_SUBTRACTOR => RelocationKind::Arm64(Arm64Reloc::Subtractor),
+                ARM64_RELOC_BRANCH26 => RelocationKind::Arm64(Arm64Reloc::Branch26),
+                ARM64_RELOC_PAGE21 => RelocationKind::Arm64(Arm64Reloc::Page21),
+                ARM64_RELOC_PAGEOFF12 => RelocationKind::Arm64(Arm64Reloc::PageOff12),
+                ARM64_RELOC_GOT_LOAD_PAGE21 => RelocationKind::Arm64(Arm64Reloc::GotLoadPage21),
+                ARM64_RELOC_GOT_LOAD_PAGEOFF12 => RelocationKind::Arm64(Arm64Reloc::GotLoadPageOff12),
+                ARM64_RELOC_POINTER_TO_GOT => RelocationKind::Arm64(Arm64Reloc::PointerToGot),
+                ARM64_RELOC_TLVP_LOAD_PAGE21 => RelocationKind::Arm64(Arm64Reloc::TlvpLoadPage21),
+                ARM64_RELOC_TLVP_LOAD_PAGEOFF12 => RelocationKind::Arm64(Arm64Reloc::TlvpLoadPageOff12),
+                ARM64_RELOC_ADDEND => RelocationKind::Arm64(Arm64Reloc::Addend),
+                ARM64_RELOC_AUTHENTICATED_POINTER => RelocationKind::Arm64(Arm64Reloc::AuthenticatedPointer),
+                _ => RelocationKind::Other(r_type),
+            },
+            _ => RelocationKind::Other(r_type),
+        }
+    }
+}
+
+/// Section relocation entry (relocation_info / scattered_relocation_info)
+#[derive(Debug)]
+pub struct Relocation {
+    /// Offset of the fixed up location from the start of the section
+    pub address: u32,
+    /// Symbol table index if `is_extern`, otherwise the 1-based section
+    /// ordinal (or R_ABS); unused for scattered relocations
+    pub symbolnum: u32,
+    pub pcrel: bool,
+    /// log2 of the fixed up location's size
+    pub length: u8,
+    pub is_extern: bool,
+    pub r_type: u8,
+    pub kind: RelocationKind,
+    pub scattered: bool,
+    /// Target address of a scattered relocation
+    pub value: u32,
+    /// Addend from a preceding ARM64_RELOC_ADDEND
+    pub addend: i64,
+    /// Name of the target symbol of an extern relocation
+    pub symbol: Option<String>,
+}
+
+impl Relocation {
+    /// Portable category of the relocation; `cpu_type` tells apart the
+    /// i386 and ARM meanings of the generic relocation types
+    pub fn category(&self, cpu_type: Machine) -> ::RelocationKind {
+        match self.kind {
+            RelocationKind::X86_64(x) => match x {
+                X86_64Reloc::Unsigned => ::RelocationKind::Absolute,
+                X86_64Reloc::Signed | X86_64Reloc::Signed1 | X86_64Reloc::Signed2 | X86_64Reloc::Signed4 |
+                X86_64Reloc::Branch => ::RelocationKind::PcRelative,
+                X86_64Reloc::GotLoad | X86_64Reloc::Got => ::RelocationKind::Got,
+                X86_64Reloc::Tlv => ::RelocationKind::Tls,
+                X86_64Reloc::Subtractor => ::RelocationKind::Other,
+            },
+            RelocationKind::Arm64(x) => match x {
+                Arm64Reloc::Unsigned | Arm64Reloc::PageOff12 | Arm64Reloc::AuthenticatedPointer => {
+                    ::RelocationKind::Absolute
+                }
+                Arm64Reloc::Branch26 | Arm64Reloc::Page21 => ::RelocationKind::PcRelative,
+                Arm64Reloc::GotLoadPage21 | Arm64Reloc::GotLoadPageOff12 | Arm64Reloc::PointerToGot => {
+                    ::RelocationKind::Got
+                }
+                Arm64Reloc::TlvpLoadPage21 | Arm64Reloc::TlvpLoadPageOff12 => ::RelocationKind::Tls,
+                Arm64Reloc::Subtractor | Arm64Reloc::Addend => ::RelocationKind::Other,
+            },
+            RelocationKind::Other(x) => match cpu_type {
+                PM_I386 if x == GENERIC_RELOC_TLV => ::RelocationKind::Tls,
+                PM_I386 if x == GENERIC_RELOC_VANILLA && self.pcrel => ::RelocationKind::PcRelative,
+                PM_I386 if x == GENERIC_RELOC_VANILLA => ::RelocationKind::Absolute,
+                PM_ARM if x == ARM_RELOC_BR24 || x == ARM_THUMB_RELOC_BR22 || x == ARM_THUMB_32BIT_BRANCH => {
+                    ::RelocationKind::PcRelative
+                }
+                PM_ARM if (x == ARM_RELOC_VANILLA || x == ARM_RELOC_HALF) && self.pcrel => ::RelocationKind::PcRelative,
+                PM_ARM if x == ARM_RELOC_VANILLA || x == ARM_RELOC_HALF => ::RelocationKind::Absolute,
+                _ => ::RelocationKind::Other,
+            },
+        }
     }
 }
 
 pub const NIDENT: usize = 4;
 
+/// Segment of this file to be mapped (32-bit)
+pub const LC_SEGMENT: u32 = 0x1;
+/// Link-edit stab symbol table info
+pub const LC_SYMTAB: u32 = 0x2;
+/// Thread state
+pub const LC_THREAD: u32 = 0x4;
+/// Unix thread state, including the initial stack
+pub const LC_UNIXTHREAD: u32 = 0x5;
+/// Dynamic link-edit symbol table information
+pub const LC_DYSYMTAB: u32 = 0xb;
+/// Dynamically linked shared library to load
+pub const LC_LOAD_DYLIB: u32 = 0xc;
+/// Install name of a dynamically linked shared library
+pub const LC_ID_DYLIB: u32 = 0xd;
+/// Segment of this file to be mapped (64-bit)
+pub const LC_SEGMENT_64: u32 = 0x19;
+/// 128-bit UUID of the image
+pub const LC_UUID: u32 = 0x1b;
+/// Code signature blob in __LINKEDIT
+pub const LC_CODE_SIGNATURE: u32 = 0x1d;
+/// Delay-loaded dynamically linked shared library
+pub const LC_LAZY_LOAD_DYLIB: u32 = 0x20;
+
+/// Load command flag: dyld must understand the command to run the image
+pub const LC_REQ_DYLD: u32 = 0x80000000;
+/// Dynamically linked shared library that may be missing at runtime
+pub const LC_LOAD_WEAK_DYLIB: u32 = 0x18 | LC_REQ_DYLD;
+/// Runpath search path
+pub const LC_RPATH: u32 = 0x1c | LC_REQ_DYLD;
+/// Dynamically linked shared library whose exports are re-exported
+pub const LC_REEXPORT_DYLIB: u32 = 0x1f | LC_REQ_DYLD;
+/// Dynamically linked shared library with an upward dependency
+pub const LC_LOAD_UPWARD_DYLIB: u32 = 0x23 | LC_REQ_DYLD;
+/// Compressed dyld information
+pub const LC_DYLD_INFO: u32 = 0x22;
+/// Compressed dyld information, required to run the image
+pub const LC_DYLD_INFO_ONLY: u32 = 0x22 | LC_REQ_DYLD;
+/// Minimum macOS version
+pub const LC_VERSION_MIN_MACOSX: u32 = 0x24;
+/// Minimum iOS version
+pub const LC_VERSION_MIN_IPHONEOS: u32 = 0x25;
+/// ULEB128 encoded function start addresses
+pub const LC_FUNCTION_STARTS: u32 = 0x26;
+/// Offset of main() and initial stack size, replacing LC_UNIXTHREAD
+pub const LC_MAIN: u32 = 0x28 | LC_REQ_DYLD;
+/// Table of data regions inside __TEXT
+pub const LC_DATA_IN_CODE: u32 = 0x29;
+/// Minimum tvOS version
+pub const LC_VERSION_MIN_TVOS: u32 = 0x2f;
+/// Minimum watchOS version
+pub const LC_VERSION_MIN_WATCHOS: u32 = 0x30;
+/// Target platform, minimum OS and SDK versions and build tools
+pub const LC_BUILD_VERSION: u32 = 0x32;
+/// Export trie, used with chained fixups instead of LC_DYLD_INFO
+pub const LC_DYLD_EXPORTS_TRIE: u32 = 0x33 | LC_REQ_DYLD;
+/// Chained fixups, replacing the LC_DYLD_INFO rebase and bind opcodes
+pub const LC_DYLD_CHAINED_FIXUPS: u32 = 0x34 | LC_REQ_DYLD;
+
+/// Relocatable object file
+pub const MH_OBJECT: u32 = 0x1;
+/// Demand paged executable file
+pub const MH_EXECUTE: u32 = 0x2;
+/// Fixed VM shared library file
+pub const MH_FVMLIB: u32 = 0x3;
+/// Core file
+pub const MH_CORE: u32 = 0x4;
+/// Preloaded executable file
+pub const MH_PRELOAD: u32 = 0x5;
+/// Dynamically bound shared library
+pub const MH_DYLIB: u32 = 0x6;
+/// Dynamic link editor
+pub const MH_DYLINKER: u32 = 0x7;
+/// Dynamically bound bundle file
+pub const MH_BUNDLE: u32 = 0x8;
+/// Shared library stub for static linking only
+pub const MH_DYLIB_STUB: u32 = 0x9;
+/// Companion file with only debug sections
+pub const MH_DSYM: u32 = 0xa;
+/// x86_64 kernel extension
+pub const MH_KEXT_BUNDLE: u32 = 0xb;
+/// Set of Mach-O files, such as a kernel collection
+pub const MH_FILESET: u32 = 0xc;
+
+/// No undefined references
+pub const MH_NOUNDEFS: u32 = 0x1;
+/// Input for the dynamic linker
+pub const MH_DYLDLINK: u32 = 0x4;
+/// Uses two-level namespace bindings
+pub const MH_TWOLEVEL: u32 = 0x80;
+/// Load the executable at a random address
+pub const MH_PIE: u32 = 0x200000;
+
 pub const MACH_HDR_MAG: u32 = 0xFEEDFACE;
 pub const MACH64_HDR_MAG: u32 = 0xFEEDFACF;
 pub const MACH_HDR_CIG: u32 = 0xCEFAEDFE;
 pub const MACH64_HDR_CIG: u32 = 0xCFFAEDFE;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Class(pub u8);
 pub const MACH_CLASS_NONE: Class = Class(0);
 pub const MACH_CLASS_32: Class = Class(1);
@@ -66,6 +489,7 @@ impl fmt::Display for Class {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Data(pub u8);
 pub const MACH_DATA_NONE: Data = Data(0);
 pub const MACH_DATA_2LSB: Data = Data(1);
@@ -89,8 +513,12 @@ impl fmt::Display for Data {
     }
 }
 
+/// 64-bit ABI capability bit of the CPU type
 pub const ABI64: i32 = 0x01000000;
+/// ILP32 ABI on 64-bit hardware capability bit of the CPU type
+pub const ABI64_32: i32 = 0x02000000;
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Machine(pub i32);
 pub const PM_ANY: Machine = Machine(-1);
 pub const PM_VAX: Machine = Machine(1);
@@ -100,6 +528,9 @@ pub const PM_I386: Machine = PM_X86;
 pub const PM_X86_64: Machine = Machine(7 | ABI64);
 pub const PM_MC98000: Machine = Machine(10);
 pub const PM_HPPA: Machine = Machine(11);
+pub const PM_ARM: Machine = Machine(12);
+pub const PM_ARM64: Machine = Machine(12 | ABI64);
+pub const PM_ARM64_32: Machine = Machine(12 | ABI64_32);
 pub const PM_MC88000: Machine = Machine(13);
 pub const PM_SPARC: Machine = Machine(14);
 pub const PM_I860: Machine = Machine(15);
@@ -111,3 +542,996 @@ impl fmt::Debug for Machine {
         write!(f, "{:#x}", self.0)
     }
 }
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let str = match *self {
+            PM_ANY => "any",
+            PM_VAX => "VAX",
+            PM_MC680X0 => "Motorola 680x0",
+            PM_X86 => "x86",
+            PM_X86_64 => "x86_64",
+            PM_MC98000 => "Motorola 98000",
+            PM_HPPA => "HPPA",
+            PM_ARM => "ARM",
+            PM_ARM64 => "ARM64",
+            PM_ARM64_32 => "ARM64_32",
+            PM_MC88000 => "Motorola 88000",
+            PM_SPARC => "SPARC",
+            PM_I860 => "Intel i860",
+            PM_POWERPC => "PowerPC",
+            PM_POWERPC64 => "PowerPC 64-bit",
+            _ => "unknown",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl Machine {
+    /// Map a CPU type and subtype in the given byte order to the generic
+    /// architecture
+    pub fn arch(&self, cpu_subtype: u32, data: Data) -> ::Arch {
+        let endian = match data {
+            MACH_DATA_2LSB => ::Endianness::Little,
+            MACH_DATA_2MSB => ::Endianness::Big,
+            _ => return ::Arch::Unknown,
+        };
+        match *self {
+            PM_I386 => ::Arch::X86(::Width::W32),
+            PM_X86_64 => ::Arch::X86(::Width::W64),
+            PM_POWERPC => ::Arch::PPC(::Width::W32, endian),
+            PM_POWERPC64 => ::Arch::PPC(::Width::W64, endian),
+            PM_ARM => {
+                match cpu_subtype & !CPU_SUBTYPE_MASK {
+                    CPU_SUBTYPE_ARM_V6M | CPU_SUBTYPE_ARM_V7M |
+                    CPU_SUBTYPE_ARM_V7EM | CPU_SUBTYPE_ARM_V8M => {
+                        ::Arch::ARM(::Width::W32, endian, ::ARMMode::Thumb, ::ARMType::MClass)
+                    }
+                    CPU_SUBTYPE_ARM_V8 => ::Arch::ARM(::Width::W32, endian, ::ARMMode::ARM, ::ARMType::V8),
+                    _ => ::Arch::ARM(::Width::W32, endian, ::ARMMode::ARM, ::ARMType::ARM),
+                }
+            }
+            PM_ARM64 => ::Arch::ARM(::Width::W64, endian, ::ARMMode::ARM, ::ARMType::V8),
+            PM_ARM64_32 => ::Arch::ARM(::Width::W32, endian, ::ARMMode::ARM, ::ARMType::V8),
+            _ => ::Arch::Unknown,
+        }
+    }
+}
+
+/// Mask for the capability bits of the CPU subtype
+pub const CPU_SUBTYPE_MASK: u32 = 0xff000000;
+/// 64-bit libraries capability bit of the CPU subtype
+pub const CPU_SUBTYPE_LIB64: u32 = 0x80000000;
+/// arm64e: the pointer authentication ABI is versioned
+pub const CPU_SUBTYPE_PTRAUTH_ABI: u32 = 0x80000000;
+/// arm64e: mask for the pointer authentication ABI version
+pub const CPU_SUBTYPE_ARM64_PTR_AUTH_MASK: u32 = 0x0f000000;
+
+pub const CPU_SUBTYPE_ARM_ALL: u32 = 0;
+pub const CPU_SUBTYPE_ARM_V4T: u32 = 5;
+pub const CPU_SUBTYPE_ARM_V6: u32 = 6;
+pub const CPU_SUBTYPE_ARM_V5TEJ: u32 = 7;
+pub const CPU_SUBTYPE_ARM_XSCALE: u32 = 8;
+pub const CPU_SUBTYPE_ARM_V7: u32 = 9;
+/// Cortex A9
+pub const CPU_SUBTYPE_ARM_V7F: u32 = 10;
+/// Swift
+pub const CPU_SUBTYPE_ARM_V7S: u32 = 11;
+/// Apple Watch
+pub const CPU_SUBTYPE_ARM_V7K: u32 = 12;
+pub const CPU_SUBTYPE_ARM_V8: u32 = 13;
+pub const CPU_SUBTYPE_ARM_V6M: u32 = 14;
+pub const CPU_SUBTYPE_ARM_V7M: u32 = 15;
+pub const CPU_SUBTYPE_ARM_V7EM: u32 = 16;
+pub const CPU_SUBTYPE_ARM_V8M: u32 = 17;
+
+pub const CPU_SUBTYPE_ARM64_ALL: u32 = 0;
+pub const CPU_SUBTYPE_ARM64_V8: u32 = 1;
+/// arm64e, with pointer authentication
+pub const CPU_SUBTYPE_ARM64E: u32 = 2;
+
+pub const CPU_SUBTYPE_ARM64_32_V8: u32 = 1;
+
+/// Pointer authentication ABI version of a versioned arm64e subtype
+pub fn ptrauth_version(cpu_type: Machine, cpu_subtype: u32) -> Option<u32> {
+    if cpu_type == PM_ARM64 && cpu_subtype & !CPU_SUBTYPE_MASK == CPU_SUBTYPE_ARM64E &&
+       cpu_subtype & CPU_SUBTYPE_PTRAUTH_ABI != 0 {
+        Some((cpu_subtype & CPU_SUBTYPE_ARM64_PTR_AUTH_MASK) >> 24)
+    } else {
+        None
+    }
+}
+
+/// Universal (fat) binary magic, 32-bit offsets
+pub const FAT_MAGIC: u32 = 0xCAFEBABE;
+/// Universal (fat) binary magic, 64-bit offsets
+pub const FAT_MAGIC_64: u32 = 0xCAFEBABF;
+
+/// Architecture slice entry of a universal binary
+#[derive(Debug)]
+pub struct FatArch {
+    pub cpu_type: Machine,
+    pub cpu_subtype: u32,
+    pub offset: u64,
+    pub size: u64,
+    pub align: u32,
+}
+
+impl FatArch {
+    /// Generic architecture of the slice; the byte order is implied by the
+    /// CPU type since the universal header does not record it
+    pub fn arch(&self) -> ::Arch {
+        let data = match self.cpu_type {
+            PM_POWERPC | PM_POWERPC64 | PM_MC680X0 | PM_SPARC | PM_HPPA => MACH_DATA_2MSB,
+            _ => MACH_DATA_2LSB,
+        };
+        self.cpu_type.arch(self.cpu_subtype, data)
+    }
+    /// CPU subtype without the capability bits
+    pub fn subtype(&self) -> u32 {
+        self.cpu_subtype & !CPU_SUBTYPE_MASK
+    }
+    pub fn ptrauth_version(&self) -> Option<u32> {
+        ptrauth_version(self.cpu_type, self.cpu_subtype)
+    }
+}
+
+/// Symbol table entry (nlist / nlist_64)
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub n_type: u8,
+    pub n_sect: u8,
+    pub n_desc: u16,
+    pub value: u64,
+}
+
+/// Mask for the debugging (stab) bits of n_type
+pub const N_STAB: u8 = 0xe0;
+/// Private external symbol bit of n_type
+pub const N_PEXT: u8 = 0x10;
+/// Mask for the type bits of n_type
+pub const N_TYPE: u8 = 0x0e;
+/// External symbol bit of n_type
+pub const N_EXT: u8 = 0x01;
+
+/// Undefined symbol (n_sect == NO_SECT)
+pub const N_UNDF: u8 = 0x0;
+/// Absolute symbol (n_sect == NO_SECT)
+pub const N_ABS: u8 = 0x2;
+/// Symbol defined in section n_sect
+pub const N_SECT: u8 = 0xe;
+/// Prebound undefined symbol (defined in a dylib)
+pub const N_PBUD: u8 = 0xc;
+/// Indirect symbol
+pub const N_INDR: u8 = 0xa;
+
+/// Symbol is not in any section
+pub const NO_SECT: u8 = 0;
+
+/// Mask for the reference type bits of n_desc
+pub const REFERENCE_TYPE: u16 = 0x7;
+/// Undefined symbol referenced through a non-lazy pointer
+pub const REFERENCE_FLAG_UNDEFINED_NON_LAZY: u16 = 0x0;
+/// Undefined symbol referenced through a lazy pointer
+pub const REFERENCE_FLAG_UNDEFINED_LAZY: u16 = 0x1;
+/// Symbol must not be dead-stripped
+pub const N_NO_DEAD_STRIP: u16 = 0x20;
+/// Symbol is a weak reference
+pub const N_WEAK_REF: u16 = 0x40;
+/// Symbol is a weak definition
+pub const N_WEAK_DEF: u16 = 0x80;
+
+/// Library ordinal of the image itself
+pub const SELF_LIBRARY_ORDINAL: u8 = 0x0;
+/// Library ordinal resolved through dynamic lookup
+pub const DYNAMIC_LOOKUP_ORDINAL: u8 = 0xfe;
+/// Library ordinal of the main executable
+pub const EXECUTABLE_ORDINAL: u8 = 0xff;
+
+impl Symbol {
+    pub fn is_stab(&self) -> bool {
+        self.n_type & N_STAB != 0
+    }
+    pub fn is_private_extern(&self) -> bool {
+        self.n_type & N_PEXT != 0
+    }
+    pub fn is_external(&self) -> bool {
+        self.n_type & N_EXT != 0
+    }
+    /// Type bits of n_type (N_UNDF, N_ABS, N_SECT, N_PBUD or N_INDR)
+    pub fn sym_type(&self) -> u8 {
+        self.n_type & N_TYPE
+    }
+    pub fn is_undefined(&self) -> bool {
+        !self.is_stab() && self.sym_type() == N_UNDF
+    }
+    pub fn is_weak_ref(&self) -> bool {
+        self.n_desc & N_WEAK_REF != 0
+    }
+    pub fn is_weak_def(&self) -> bool {
+        self.n_desc & N_WEAK_DEF != 0
+    }
+    pub fn is_lazy(&self) -> bool {
+        self.n_desc & REFERENCE_TYPE == REFERENCE_FLAG_UNDEFINED_LAZY
+    }
+    /// Two-level namespace ordinal of the dylib defining an undefined symbol
+    pub fn library_ordinal(&self) -> u8 {
+        (self.n_desc >> 8) as u8
+    }
+}
+
+/// Indirect symbol table entry for a local symbol that was stripped
+pub const INDIRECT_SYMBOL_LOCAL: u32 = 0x80000000;
+/// Indirect symbol table entry for an absolute symbol
+pub const INDIRECT_SYMBOL_ABS: u32 = 0x40000000;
+
+/// LC_DYSYMTAB contents. Symbol ranges index the LC_SYMTAB entries.
+#[derive(Debug)]
+pub struct DysymtabCommand {
+    pub ilocalsym: u32,
+    pub nlocalsym: u32,
+    pub iextdefsym: u32,
+    pub nextdefsym: u32,
+    pub iundefsym: u32,
+    pub nundefsym: u32,
+    pub tocoff: u32,
+    pub ntoc: u32,
+    pub modtaboff: u32,
+    pub nmodtab: u32,
+    pub extrefsymoff: u32,
+    pub nextrefsyms: u32,
+    pub indirectsymoff: u32,
+    pub nindirectsyms: u32,
+    pub extreloff: u32,
+    pub nextrel: u32,
+    pub locreloff: u32,
+    pub nlocrel: u32,
+}
+
+/// Table of contents entry of a pre-two-level-namespace dylib
+#[derive(Debug)]
+pub struct TocEntry {
+    pub symbol_index: u32,
+    pub module_index: u32,
+}
+
+/// Module table entry (dylib_module / dylib_module_64)
+#[derive(Debug)]
+pub struct Module {
+    pub name: String,
+    pub iextdefsym: u32,
+    pub nextdefsym: u32,
+    pub irefsym: u32,
+    pub nrefsym: u32,
+    pub ilocalsym: u32,
+    pub nlocalsym: u32,
+    pub iextrel: u32,
+    pub nextrel: u32,
+    pub iinit_iterm: u32,
+    pub ninit_nterm: u32,
+    pub objc_module_info_addr: u64,
+    pub objc_module_info_size: u32,
+}
+
+/// Decoded LC_DYSYMTAB information
+#[derive(Debug)]
+pub struct Dysymtab {
+    pub cmd: DysymtabCommand,
+    /// Symbol table indices, or INDIRECT_SYMBOL_LOCAL / INDIRECT_SYMBOL_ABS
+    pub indirect_symbols: Vec<u32>,
+    pub toc: Vec<TocEntry>,
+    pub modules: Vec<Module>,
+}
+
+/// Stub or symbol pointer named through the indirect symbol table
+#[derive(Debug)]
+pub struct IndirectSymbol {
+    /// Address of the stub or pointer
+    pub address: u64,
+    pub seg_name: String,
+    pub sect_name: String,
+    /// Raw indirect symbol table entry
+    pub index: u32,
+    /// Name of the referenced symbol, unless it is local or absolute
+    pub symbol: Option<String>,
+}
+
+/// Packed version number (xxxx.yy.zz)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32);
+
+impl Version {
+    pub fn major(&self) -> u32 {
+        self.0 >> 16
+    }
+    pub fn minor(&self) -> u32 {
+        (self.0 >> 8) & 0xff
+    }
+    pub fn patch(&self) -> u32 {
+        self.0 & 0xff
+    }
+}
+
+impl fmt::Debug for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major(), self.minor(), self.patch())
+    }
+}
+
+/// Dynamic library reference from a dylib load command
+#[derive(Debug)]
+pub struct Dylib {
+    pub cmd: u32,
+    pub name: String,
+    pub timestamp: u32,
+    pub current_version: Version,
+    pub compat_version: Version,
+}
+
+impl Dylib {
+    pub fn is_weak(&self) -> bool {
+        self.cmd == LC_LOAD_WEAK_DYLIB
+    }
+    pub fn is_reexport(&self) -> bool {
+        self.cmd == LC_REEXPORT_DYLIB
+    }
+    pub fn is_lazy(&self) -> bool {
+        self.cmd == LC_LAZY_LOAD_DYLIB
+    }
+    pub fn is_upward(&self) -> bool {
+        self.cmd == LC_LOAD_UPWARD_DYLIB
+    }
+}
+
+/// i386 thread state flavor (x86_THREAD_STATE32)
+pub const X86_THREAD_STATE32: u32 = 1;
+/// x86_64 thread state flavor (x86_THREAD_STATE64)
+pub const X86_THREAD_STATE64: u32 = 4;
+/// Generic x86 thread state flavor, prefixed by the real flavor and count
+pub const X86_THREAD_STATE: u32 = 7;
+/// 32-bit ARM thread state flavor
+pub const ARM_THREAD_STATE: u32 = 1;
+/// 64-bit ARM thread state flavor
+pub const ARM_THREAD_STATE64: u32 = 6;
+
+/// Entry point load command contents
+#[derive(Clone, Copy, Debug)]
+pub enum EntryPoint {
+    /// LC_MAIN: file offset of main() and initial stack size (0 for default)
+    Main { entryoff: u64, stack_size: u64 },
+    /// LC_UNIXTHREAD: initial program counter of the thread state
+    UnixThread { pc: u64 },
+}
+
+/// Platform identifier of LC_BUILD_VERSION
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Platform(pub u32);
+pub const PLATFORM_UNKNOWN: Platform = Platform(0);
+pub const PLATFORM_MACOS: Platform = Platform(1);
+pub const PLATFORM_IOS: Platform = Platform(2);
+pub const PLATFORM_TVOS: Platform = Platform(3);
+pub const PLATFORM_WATCHOS: Platform = Platform(4);
+pub const PLATFORM_BRIDGEOS: Platform = Platform(5);
+pub const PLATFORM_MACCATALYST: Platform = Platform(6);
+pub const PLATFORM_IOSSIMULATOR: Platform = Platform(7);
+pub const PLATFORM_TVOSSIMULATOR: Platform = Platform(8);
+pub const PLATFORM_WATCHOSSIMULATOR: Platform = Platform(9);
+pub const PLATFORM_DRIVERKIT: Platform = Platform(10);
+pub const PLATFORM_VISIONOS: Platform = Platform(11);
+pub const PLATFORM_VISIONOSSIMULATOR: Platform = Platform(12);
+
+impl Platform {
+    pub fn is_simulator(&self) -> bool {
+        match *self {
+            PLATFORM_IOSSIMULATOR | PLATFORM_TVOSSIMULATOR |
+            PLATFORM_WATCHOSSIMULATOR | PLATFORM_VISIONOSSIMULATOR => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let str = match *self {
+            PLATFORM_UNKNOWN => "unknown",
+            PLATFORM_MACOS => "macOS",
+            PLATFORM_IOS => "iOS",
+            PLATFORM_TVOS => "tvOS",
+            PLATFORM_WATCHOS => "watchOS",
+            PLATFORM_BRIDGEOS => "bridgeOS",
+            PLATFORM_MACCATALYST => "Mac Catalyst",
+            PLATFORM_IOSSIMULATOR => "iOS Simulator",
+            PLATFORM_TVOSSIMULATOR => "tvOS Simulator",
+            PLATFORM_WATCHOSSIMULATOR => "watchOS Simulator",
+            PLATFORM_DRIVERKIT => "DriverKit",
+            PLATFORM_VISIONOS => "visionOS",
+            PLATFORM_VISIONOSSIMULATOR => "visionOS Simulator",
+            _ => "unknown",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Build tool identifier of LC_BUILD_VERSION
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Tool(pub u32);
+pub const TOOL_CLANG: Tool = Tool(1);
+pub const TOOL_SWIFT: Tool = Tool(2);
+pub const TOOL_LD: Tool = Tool(3);
+pub const TOOL_LLD: Tool = Tool(4);
+
+impl fmt::Debug for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl fmt::Display for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let str = match *self {
+            TOOL_CLANG => "clang",
+            TOOL_SWIFT => "swift",
+            TOOL_LD => "ld",
+            TOOL_LLD => "lld",
+            _ => "unknown",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[derive(Debug)]
+pub struct BuildTool {
+    pub tool: Tool,
+    pub version: Version,
+}
+
+/// LC_BUILD_VERSION contents
+#[derive(Debug)]
+pub struct BuildVersion {
+    pub platform: Platform,
+    pub minos: Version,
+    pub sdk: Version,
+    pub tools: Vec<BuildTool>,
+}
+
+/// Legacy LC_VERSION_MIN_* contents
+#[derive(Debug)]
+pub struct VersionMin {
+    pub cmd: u32,
+    pub version: Version,
+    pub sdk: Version,
+}
+
+impl VersionMin {
+    /// Platform implied by the load command type
+    pub fn platform(&self) -> Platform {
+        match self.cmd {
+            LC_VERSION_MIN_MACOSX => PLATFORM_MACOS,
+            LC_VERSION_MIN_IPHONEOS => PLATFORM_IOS,
+            LC_VERSION_MIN_TVOS => PLATFORM_TVOS,
+            LC_VERSION_MIN_WATCHOS => PLATFORM_WATCHOS,
+            _ => PLATFORM_UNKNOWN,
+        }
+    }
+}
+
+/// LC_DYLD_INFO / LC_DYLD_INFO_ONLY contents: file offsets and sizes of the
+/// compressed dyld information in __LINKEDIT
+#[derive(Debug)]
+pub struct DyldInfo {
+    pub rebase_off: u32,
+    pub rebase_size: u32,
+    pub bind_off: u32,
+    pub bind_size: u32,
+    pub weak_bind_off: u32,
+    pub weak_bind_size: u32,
+    pub lazy_bind_off: u32,
+    pub lazy_bind_size: u32,
+    pub export_off: u32,
+    pub export_size: u32,
+}
+
+pub const REBASE_TYPE_POINTER: u8 = 1;
+pub const REBASE_TYPE_TEXT_ABSOLUTE32: u8 = 2;
+pub const REBASE_TYPE_TEXT_PCREL32: u8 = 3;
+
+pub const REBASE_OPCODE_MASK: u8 = 0xf0;
+pub const REBASE_IMMEDIATE_MASK: u8 = 0x0f;
+pub const REBASE_OPCODE_DONE: u8 = 0x00;
+pub const REBASE_OPCODE_SET_TYPE_IMM: u8 = 0x10;
+pub const REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x20;
+pub const REBASE_OPCODE_ADD_ADDR_ULEB: u8 = 0x30;
+pub const REBASE_OPCODE_ADD_ADDR_IMM_SCALED: u8 = 0x40;
+pub const REBASE_OPCODE_DO_REBASE_IMM_TIMES: u8 = 0x50;
+pub const REBASE_OPCODE_DO_REBASE_ULEB_TIMES: u8 = 0x60;
+pub const REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB: u8 = 0x70;
+pub const REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB: u8 = 0x80;
+
+pub const BIND_TYPE_POINTER: u8 = 1;
+pub const BIND_TYPE_TEXT_ABSOLUTE32: u8 = 2;
+pub const BIND_TYPE_TEXT_PCREL32: u8 = 3;
+
+/// Special library ordinal: this image
+pub const BIND_SPECIAL_DYLIB_SELF: i64 = 0;
+/// Special library ordinal: the main executable
+pub const BIND_SPECIAL_DYLIB_MAIN_EXECUTABLE: i64 = -1;
+/// Special library ordinal: flat namespace lookup
+pub const BIND_SPECIAL_DYLIB_FLAT_LOOKUP: i64 = -2;
+/// Special library ordinal: weak definition lookup
+pub const BIND_SPECIAL_DYLIB_WEAK_LOOKUP: i64 = -3;
+
+/// Missing symbol is bound to zero instead of failing
+pub const BIND_SYMBOL_FLAGS_WEAK_IMPORT: u8 = 0x1;
+/// Strong definition overriding weak definitions (weak binding info only)
+pub const BIND_SYMBOL_FLAGS_NON_WEAK_DEFINITION: u8 = 0x8;
+
+pub const BIND_OPCODE_MASK: u8 = 0xf0;
+pub const BIND_IMMEDIATE_MASK: u8 = 0x0f;
+pub const BIND_OPCODE_DONE: u8 = 0x00;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_IMM: u8 = 0x10;
+pub const BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB: u8 = 0x20;
+pub const BIND_OPCODE_SET_DYLIB_SPECIAL_IMM: u8 = 0x30;
+pub const BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM: u8 = 0x40;
+pub const BIND_OPCODE_SET_TYPE_IMM: u8 = 0x50;
+pub const BIND_OPCODE_SET_ADDEND_SLEB: u8 = 0x60;
+pub const BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB: u8 = 0x70;
+pub const BIND_OPCODE_ADD_ADDR_ULEB: u8 = 0x80;
+pub const BIND_OPCODE_DO_BIND: u8 = 0x90;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB: u8 = 0xa0;
+pub const BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED: u8 = 0xb0;
+pub const BIND_OPCODE_DO_BIND_ULEB_TIMES_SKIPPING_ULEB: u8 = 0xc0;
+pub const BIND_OPCODE_THREADED: u8 = 0xd0;
+pub const BIND_SUBOPCODE_THREADED_SET_BIND_ORDINAL_TABLE_SIZE_ULEB: u8 = 0x00;
+pub const BIND_SUBOPCODE_THREADED_APPLY: u8 = 0x01;
+
+/// Location the dynamic loader slides by the image's load address
+#[derive(Debug)]
+pub struct Rebase {
+    pub seg_index: u8,
+    pub seg_offset: u64,
+    pub address: u64,
+    pub rebase_type: u8,
+}
+
+/// Which dyld binding stream a bind record came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindKind {
+    Regular,
+    Weak,
+    Lazy,
+}
+
+/// Location the dynamic loader binds to an imported symbol
+#[derive(Debug)]
+pub struct Bind {
+    pub kind: BindKind,
+    pub seg_index: u8,
+    pub seg_offset: u64,
+    pub address: u64,
+    pub bind_type: u8,
+    pub symbol_name: String,
+    pub symbol_flags: u8,
+    /// Library ordinal, or one of the BIND_SPECIAL_DYLIB_* values
+    pub library_ordinal: i64,
+    /// Install name of the library the ordinal refers to
+    pub library: Option<String>,
+    pub addend: i64,
+}
+
+impl Bind {
+    pub fn is_weak_import(&self) -> bool {
+        self.symbol_flags & BIND_SYMBOL_FLAGS_WEAK_IMPORT != 0
+    }
+}
+
+/// Generic load command pointing at a blob of data in __LINKEDIT
+#[derive(Debug)]
+pub struct LinkeditData {
+    pub cmd: u32,
+    pub dataoff: u32,
+    pub datasize: u32,
+}
+
+pub const DICE_KIND_DATA: u16 = 0x0001;
+pub const DICE_KIND_JUMP_TABLE8: u16 = 0x0002;
+pub const DICE_KIND_JUMP_TABLE16: u16 = 0x0003;
+pub const DICE_KIND_JUMP_TABLE32: u16 = 0x0004;
+pub const DICE_KIND_ABS_JUMP_TABLE32: u16 = 0x0005;
+
+/// LC_DATA_IN_CODE entry: data embedded in the instruction stream
+#[derive(Debug)]
+pub struct DataInCode {
+    /// File offset of the data
+    pub offset: u32,
+    pub length: u16,
+    /// One of the DICE_KIND_* constants
+    pub kind: u16,
+    /// Virtual address of the data
+    pub address: u64,
+}
+
+impl DataInCode {
+    pub fn is_jump_table(&self) -> bool {
+        match self.kind {
+            DICE_KIND_JUMP_TABLE8 | DICE_KIND_JUMP_TABLE16 |
+            DICE_KIND_JUMP_TABLE32 | DICE_KIND_ABS_JUMP_TABLE32 => true,
+            _ => false,
+        }
+    }
+}
+
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_32_CACHE: u16 = 4;
+pub const DYLD_CHAINED_PTR_32_FIRMWARE: u16 = 5;
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+pub const DYLD_CHAINED_PTR_ARM64E_KERNEL: u16 = 7;
+pub const DYLD_CHAINED_PTR_64_KERNEL_CACHE: u16 = 8;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+pub const DYLD_CHAINED_PTR_ARM64E_FIRMWARE: u16 = 10;
+pub const DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE: u16 = 11;
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+
+/// Page has no fixups
+pub const DYLD_CHAINED_PTR_START_NONE: u16 = 0xffff;
+/// Page has multiple chain starts (32-bit formats only)
+pub const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+/// Last chain start of a page with multiple starts
+pub const DYLD_CHAINED_PTR_START_LAST: u16 = 0x8000;
+
+/// dyld_chained_fixups_header
+#[derive(Debug)]
+pub struct ChainedFixupsHeader {
+    pub fixups_version: u32,
+    pub starts_offset: u32,
+    pub imports_offset: u32,
+    pub symbols_offset: u32,
+    pub imports_count: u32,
+    pub imports_format: u32,
+    pub symbols_format: u32,
+}
+
+/// dyld_chained_starts_in_segment, for segments that have fixups
+#[derive(Debug)]
+pub struct ChainedStarts {
+    pub seg_index: u32,
+    pub page_size: u16,
+    pub pointer_format: u16,
+    pub segment_offset: u64,
+    pub max_valid_pointer: u32,
+    pub page_starts: Vec<u16>,
+}
+
+/// Entry of the chained fixups imports table
+#[derive(Debug)]
+pub struct ChainedImport {
+    /// Library ordinal, or one of the BIND_SPECIAL_DYLIB_* values
+    pub library_ordinal: i64,
+    /// Install name of the library the ordinal refers to
+    pub library: Option<String>,
+    pub weak_import: bool,
+    pub name: String,
+    pub addend: i64,
+}
+
+/// arm64e pointer authentication parameters of a fixup
+#[derive(Clone, Copy, Debug)]
+pub struct PtrAuth {
+    pub key: u8,
+    pub addr_div: bool,
+    pub diversity: u16,
+}
+
+/// Chained pointer the dynamic loader rewrites to `target` plus the slide
+#[derive(Debug)]
+pub struct ChainedRebase {
+    pub seg_index: u32,
+    pub seg_offset: u64,
+    pub address: u64,
+    /// Unslid target address, including any high8 top byte
+    pub target: u64,
+    pub auth: Option<PtrAuth>,
+}
+
+/// Chained pointer the dynamic loader binds to an imported symbol
+#[derive(Debug)]
+pub struct ChainedBind {
+    pub seg_index: u32,
+    pub seg_offset: u64,
+    pub address: u64,
+    /// Index into the imports table
+    pub import: u32,
+    pub symbol_name: String,
+    pub library_ordinal: i64,
+    pub library: Option<String>,
+    pub weak_import: bool,
+    /// Import addend plus any addend encoded in the pointer
+    pub addend: i64,
+    pub auth: Option<PtrAuth>,
+}
+
+/// Decoded LC_DYLD_CHAINED_FIXUPS information
+#[derive(Debug)]
+pub struct ChainedFixups {
+    pub header: ChainedFixupsHeader,
+    pub imports: Vec<ChainedImport>,
+    pub starts: Vec<ChainedStarts>,
+    pub rebases: Vec<ChainedRebase>,
+    pub binds: Vec<ChainedBind>,
+}
+
+/// Mask for the kind bits of export flags
+pub const EXPORT_SYMBOL_FLAGS_KIND_MASK: u64 = 0x03;
+pub const EXPORT_SYMBOL_FLAGS_KIND_REGULAR: u64 = 0x00;
+pub const EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL: u64 = 0x01;
+pub const EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE: u64 = 0x02;
+pub const EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION: u64 = 0x04;
+pub const EXPORT_SYMBOL_FLAGS_REEXPORT: u64 = 0x08;
+pub const EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER: u64 = 0x10;
+pub const EXPORT_SYMBOL_FLAGS_STATIC_RESOLVER: u64 = 0x20;
+
+/// Source of a re-exported symbol
+#[derive(Debug)]
+pub struct ReExport {
+    pub library_ordinal: u64,
+    /// Install name of the library the ordinal refers to
+    pub library: Option<String>,
+    /// Name of the symbol in the source library
+    pub name: String,
+}
+
+/// Exported symbol from the export trie
+#[derive(Debug)]
+pub struct Export {
+    pub name: String,
+    pub flags: u64,
+    /// Raw trie value: offset from the mach header, or the absolute value
+    pub value: u64,
+    /// Unslid address of the symbol; 0 for re-exports
+    pub address: u64,
+    /// Address of the resolver function of a stub-and-resolver export
+    pub resolver: Option<u64>,
+    pub reexport: Option<ReExport>,
+}
+
+impl Export {
+    pub fn kind(&self) -> u64 {
+        self.flags & EXPORT_SYMBOL_FLAGS_KIND_MASK
+    }
+    pub fn is_thread_local(&self) -> bool {
+        self.kind() == EXPORT_SYMBOL_FLAGS_KIND_THREAD_LOCAL
+    }
+    pub fn is_absolute(&self) -> bool {
+        self.kind() == EXPORT_SYMBOL_FLAGS_KIND_ABSOLUTE
+    }
+    pub fn is_weak(&self) -> bool {
+        self.flags & EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION != 0
+    }
+    pub fn is_reexport(&self) -> bool {
+        self.flags & EXPORT_SYMBOL_FLAGS_REEXPORT != 0
+    }
+    pub fn is_stub_and_resolver(&self) -> bool {
+        self.flags & EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER != 0
+    }
+}
+
+pub const CSMAGIC_REQUIREMENT: u32 = 0xfade0c00;
+pub const CSMAGIC_REQUIREMENTS: u32 = 0xfade0c01;
+pub const CSMAGIC_CODEDIRECTORY: u32 = 0xfade0c02;
+pub const CSMAGIC_EMBEDDED_SIGNATURE: u32 = 0xfade0cc0;
+pub const CSMAGIC_DETACHED_SIGNATURE: u32 = 0xfade0cc1;
+pub const CSMAGIC_BLOBWRAPPER: u32 = 0xfade0b01;
+pub const CSMAGIC_EMBEDDED_ENTITLEMENTS: u32 = 0xfade7171;
+pub const CSMAGIC_EMBEDDED_DER_ENTITLEMENTS: u32 = 0xfade7172;
+
+pub const CSSLOT_CODEDIRECTORY: u32 = 0;
+pub const CSSLOT_INFOSLOT: u32 = 1;
+pub const CSSLOT_REQUIREMENTS: u32 = 2;
+pub const CSSLOT_RESOURCEDIR: u32 = 3;
+pub const CSSLOT_APPLICATION: u32 = 4;
+pub const CSSLOT_ENTITLEMENTS: u32 = 5;
+pub const CSSLOT_DER_ENTITLEMENTS: u32 = 7;
+pub const CSSLOT_ALTERNATE_CODEDIRECTORIES: u32 = 0x1000;
+pub const CSSLOT_SIGNATURESLOT: u32 = 0x10000;
+
+pub const CS_HASHTYPE_SHA1: u8 = 1;
+pub const CS_HASHTYPE_SHA256: u8 = 2;
+pub const CS_HASHTYPE_SHA256_TRUNCATED: u8 = 3;
+pub const CS_HASHTYPE_SHA384: u8 = 4;
+
+/// First code directory version with a scatter offset
+pub const CS_SUPPORTSSCATTER: u32 = 0x20100;
+/// First code directory version with a team ID
+pub const CS_SUPPORTSTEAMID: u32 = 0x20200;
+/// First code directory version with a 64-bit code limit
+pub const CS_SUPPORTSCODELIMIT64: u32 = 0x20300;
+/// First code directory version with executable segment information
+pub const CS_SUPPORTSEXECSEG: u32 = 0x20400;
+/// First code directory version with a hardened runtime version
+pub const CS_SUPPORTSRUNTIME: u32 = 0x20500;
+
+/// Ad-hoc signed, without a certificate chain
+pub const CS_ADHOC: u32 = 0x00000002;
+/// Hardened runtime
+pub const CS_RUNTIME: u32 = 0x00010000;
+/// Signed by the linker rather than codesign
+pub const CS_LINKER_SIGNED: u32 = 0x00020000;
+
+/// CodeDirectory blob of an embedded signature
+#[derive(Debug)]
+pub struct CodeDirectory {
+    pub version: u32,
+    pub flags: u32,
+    pub hash_type: u8,
+    pub hash_size: u8,
+    pub platform: u8,
+    /// Page size in bytes; 0 means a single page covering the code limit
+    pub page_size: u64,
+    pub identifier: String,
+    pub team_id: Option<String>,
+    /// Number of bytes of the file covered by the code slots
+    pub code_limit: u64,
+    pub exec_seg_base: Option<u64>,
+    pub exec_seg_limit: Option<u64>,
+    pub exec_seg_flags: Option<u64>,
+    /// Hardened runtime SDK version
+    pub runtime: Option<Version>,
+    /// Special slot hashes; entry N - 1 is slot N (CSSLOT_INFOSLOT, ...)
+    pub special_slots: Vec<Vec<u8>>,
+    /// Page hashes
+    pub code_slots: Vec<Vec<u8>>,
+    /// CDHash: the code directory hash truncated to 20 bytes
+    pub cdhash: Option<Vec<u8>>,
+    pub raw: Vec<u8>,
+}
+
+/// Blob of an embedded signature SuperBlob
+#[derive(Debug)]
+pub struct CodeSignatureBlob {
+    pub slot: u32,
+    pub magic: u32,
+    /// Complete blob, including its magic and length
+    pub data: Vec<u8>,
+}
+
+/// Decoded LC_CODE_SIGNATURE SuperBlob
+#[derive(Debug)]
+pub struct CodeSignature {
+    pub blobs: Vec<CodeSignatureBlob>,
+    /// Primary code directory first, then any alternates
+    pub code_directories: Vec<CodeDirectory>,
+    pub requirements: Option<Vec<u8>>,
+    /// Entitlements property list (XML)
+    pub entitlements: Option<String>,
+    /// DER encoded entitlements
+    pub der_entitlements: Option<Vec<u8>>,
+    /// CMS signature (DER), empty for ad-hoc signatures
+    pub cms: Option<Vec<u8>>,
+}
+
+/// Result of checking a code directory against the file contents
+#[derive(Debug)]
+pub struct SignatureVerification {
+    pub hash_type: u8,
+    /// Indices of code slots whose hash does not match the file
+    pub bad_pages: Vec<usize>,
+    /// Special slots whose hash does not match the embedded blob
+    pub bad_special_slots: Vec<usize>,
+}
+
+impl SignatureVerification {
+    pub fn is_valid(&self) -> bool {
+        self.bad_pages.is_empty() && self.bad_special_slots.is_empty()
+    }
+}
+
+/// class_ro_t flag: the class is a metaclass
+pub const RO_META: u32 = 0x1;
+/// class_ro_t flag: the class is a root class
+pub const RO_ROOT: u32 = 0x2;
+/// method_list_t flag: entries are 32-bit relative offsets
+pub const METHOD_LIST_IS_RELATIVE: u32 = 0x80000000;
+
+/// Objective-C method_t
+#[derive(Debug)]
+pub struct ObjcMethod {
+    /// Selector name
+    pub name: String,
+    /// Type encoding
+    pub types: String,
+    /// Implementation address, 0 for protocol methods
+    pub imp: u64,
+}
+
+/// Objective-C ivar_t
+#[derive(Debug)]
+pub struct ObjcIvar {
+    pub name: String,
+    pub type_encoding: String,
+    /// Offset of the ivar in the instance
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Objective-C property_t
+#[derive(Debug)]
+pub struct ObjcProperty {
+    pub name: String,
+    pub attributes: String,
+}
+
+/// Objective-C class, from its class_t and class_ro_t
+#[derive(Debug)]
+pub struct ObjcClass {
+    /// Address of the class_t
+    pub address: u64,
+    pub name: String,
+    /// Superclass name; classes from other images are named through their
+    /// bind symbol
+    pub superclass: Option<String>,
+    /// class_ro_t flags (RO_*)
+    pub flags: u32,
+    pub instance_size: u32,
+    pub instance_methods: Vec<ObjcMethod>,
+    /// Methods of the metaclass
+    pub class_methods: Vec<ObjcMethod>,
+    pub ivars: Vec<ObjcIvar>,
+    /// Names of adopted protocols
+    pub protocols: Vec<String>,
+    pub properties: Vec<ObjcProperty>,
+}
+
+/// Objective-C category_t
+#[derive(Debug)]
+pub struct ObjcCategory {
+    pub address: u64,
+    pub name: String,
+    /// Name of the extended class
+    pub class: Option<String>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+    pub protocols: Vec<String>,
+    pub properties: Vec<ObjcProperty>,
+}
+
+/// Objective-C protocol_t
+#[derive(Debug)]
+pub struct ObjcProtocol {
+    pub address: u64,
+    pub name: String,
+    /// Names of inherited protocols
+    pub protocols: Vec<String>,
+    pub instance_methods: Vec<ObjcMethod>,
+    pub class_methods: Vec<ObjcMethod>,
+    pub optional_instance_methods: Vec<ObjcMethod>,
+    pub optional_class_methods: Vec<ObjcMethod>,
+    pub properties: Vec<ObjcProperty>,
+}
+
+/// Objective-C runtime metadata of an image
+#[derive(Debug)]
+pub struct ObjcMetadata {
+    pub classes: Vec<ObjcClass>,
+    pub categories: Vec<ObjcCategory>,
+    pub protocols: Vec<ObjcProtocol>,
+    /// Selectors referenced from __objc_selrefs
+    pub selectors: Vec<String>,
+}
@@ -7,7 +7,7 @@ use byteorder;
 use byteorder::ReadBytesExt;
 use pe::types;
 use std::collections::HashMap;
-use {Error, Object, Section};
+use {non_fatal, read_file, Error, FileData, Object, Section};
 
 macro_rules! read_u8 {
     ($io:ident) => (
@@ -33,10 +33,363 @@ macro_rules! read_u64 {
     );
 }
 
+fn get_pe_string(data: &[u8], start: usize) -> String {
+    let tail = data.get(start..).unwrap_or(&[]);
+    let end = tail.iter().position(|x| *x == 0).unwrap_or(tail.len());
+    String::from_utf8_lossy(&tail[..end]).into_owned()
+}
+
+/// Data mapped at `rva`, up to the end of the section containing it
+fn rva_data<'a>(sections: &'a [Section], base_img: u64, rva: u32) -> Option<&'a [u8]> {
+    let addr = base_img.wrapping_add(rva as u64);
+    sections.iter()
+        .find(|x| addr >= x.addr && addr - x.addr < x.data.len() as u64)
+        .map(|x| &x.data[(addr - x.addr) as usize..])
+}
+
+fn rva_u32(sections: &[Section], base_img: u64, rva: u32) -> Result<u32, Box<error::Error>> {
+    match rva_data(sections, base_img, rva) {
+        Some(mut x) => Ok(try!(read_u32!(x))),
+        None => Err(Box::new(Error::from("RVA not mapped by any section"))),
+    }
+}
+
+/// `rva + off`, failing rather than wrapping around
+fn rva_offset(rva: u32, off: u64) -> Result<u32, Box<error::Error>> {
+    match (rva as u64).checked_add(off) {
+        Some(x) if x <= u32::MAX as u64 => Ok(x as u32),
+        _ => Err(Box::new(Error::from("RVA out of range"))),
+    }
+}
+
+fn read_coff_symbols<R: io::Read + io::Seek>(r: &mut R, sym_tab_ptr: u32, num_sym: u32)
+                                             -> Result<Vec<types::Symbol>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if sym_tab_ptr == 0 || num_sym == 0 {
+        return Ok(ret);
+    }
+    // The string table directly follows the symbol table; its size
+    // includes the size field itself
+    let strtab_off = sym_tab_ptr as u64 + num_sym as u64 * 18;
+    try!(r.seek(io::SeekFrom::Start(strtab_off)));
+    let strtab_size = read_u32!(r).unwrap_or(0) as u64;
+    let mut strtab = vec![0u8; 4];
+    try!(io::Read::by_ref(r).take(strtab_size.saturating_sub(4)).read_to_end(&mut strtab));
+
+    try!(r.seek(io::SeekFrom::Start(sym_tab_ptr as u64)));
+    let mut i = 0;
+    while i < num_sym {
+        let mut raw_name = [0u8; 8];
+        try!(r.read_exact(&mut raw_name));
+        let name = if raw_name[..4] == [0, 0, 0, 0] {
+            let off = try!(io::Cursor::new(&raw_name[4..]).read_u32::<byteorder::LittleEndian>());
+            get_pe_string(&strtab, off as usize)
+        } else {
+            get_pe_string(&raw_name, 0)
+        };
+        let value = try!(read_u32!(r));
+        let sect_no = try!(read_u16!(r));
+        let sym_type = try!(read_u16!(r));
+        let storage_class = try!(read_u8!(r));
+        let num_aux_sym = try!(read_u8!(r));
+        try!(r.seek(io::SeekFrom::Current(num_aux_sym as i64 * 18)));
+        ret.push(types::Symbol {
+            name: ffi::CString::new(name).unwrap(),
+            value: value,
+            sect_no: sect_no,
+            sym_type: sym_type,
+            storage_class: storage_class,
+            num_aux_sym: num_aux_sym,
+        });
+        i += 1 + num_aux_sym as u32;
+    }
+    Ok(ret)
+}
+
+fn read_exports(sections: &[Section], base_img: u64, dir: types::DataDirectory)
+                -> Result<Vec<types::Export>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if dir.virt_addr == 0 {
+        return Ok(ret);
+    }
+    let ord_base = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 16))));
+    let num_funcs = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 20))));
+    let num_names = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 24))));
+    let funcs = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 28))));
+    let names = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 32))));
+    let ordinals = try!(rva_u32(sections, base_img, try!(rva_offset(dir.virt_addr, 36))));
+
+    // Several names may export the same function
+    let mut func_names: HashMap<u32, Vec<String>> = HashMap::new();
+    for i in 0..num_names {
+        let name_rva = try!(rva_u32(sections, base_img, try!(rva_offset(names, i as u64 * 4))));
+        let index = match rva_data(sections, base_img, try!(rva_offset(ordinals, i as u64 * 2))) {
+            Some(mut x) => try!(read_u16!(x)) as u32,
+            None => return Err(Box::new(Error::from("invalid export ordinal table"))),
+        };
+        let name = get_pe_string(rva_data(sections, base_img, name_rva).unwrap_or(&[]), 0);
+        func_names.entry(index).or_insert_with(Vec::new).push(name);
+    }
+
+    for i in 0..num_funcs {
+        let rva = try!(rva_u32(sections, base_img, try!(rva_offset(funcs, i as u64 * 4))));
+        if rva == 0 {
+            continue;
+        }
+        // Addresses inside the export directory name a forwarded export
+        let forwarder = if rva >= dir.virt_addr && rva - dir.virt_addr < dir.size {
+            Some(get_pe_string(rva_data(sections, base_img, rva).unwrap_or(&[]), 0))
+        } else {
+            None
+        };
+        let names = match func_names.remove(&i) {
+            Some(x) => x.into_iter().map(Some).collect(),
+            None => vec![None],
+        };
+        for name in names {
+            ret.push(types::Export {
+                name: name,
+                ordinal: ord_base.wrapping_add(i),
+                rva: rva,
+                address: base_img.wrapping_add(rva as u64),
+                forwarder: forwarder.clone(),
+            });
+        }
+    }
+    Ok(ret)
+}
+
+/// Read the import directory, or the delay import directory if `delay` is set
+fn read_imports(sections: &[Section], base_img: u64, pe64: bool,
+                dir: types::DataDirectory, delay: bool) -> Result<Vec<types::Import>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if dir.virt_addr == 0 {
+        return Ok(ret);
+    }
+    let (thunk_size, ord_flag): (u64, u64) = if pe64 { (8, 1 << 63) } else { (4, 1 << 31) };
+    let mut desc = dir.virt_addr;
+    loop {
+        let (name_rva, lookup, iat) = if delay {
+            let attrs = try!(rva_u32(sections, base_img, desc));
+            // Old delay descriptors hold virtual addresses
+            let fix = |x: u32| if attrs & types::DELAYLOAD_RVA_BASED != 0 || x == 0 {
+                x
+            } else {
+                (x as u64).wrapping_sub(base_img) as u32
+            };
+            let name_rva = fix(try!(rva_u32(sections, base_img, try!(rva_offset(desc, 4)))));
+            let iat = fix(try!(rva_u32(sections, base_img, try!(rva_offset(desc, 12)))));
+            let int = fix(try!(rva_u32(sections, base_img, try!(rva_offset(desc, 16)))));
+            (name_rva, int, iat)
+        } else {
+            let original_first_thunk = try!(rva_u32(sections, base_img, desc));
+            let name_rva = try!(rva_u32(sections, base_img, try!(rva_offset(desc, 12))));
+            let first_thunk = try!(rva_u32(sections, base_img, try!(rva_offset(desc, 16))));
+            // Without an import lookup table the unbound IAT doubles as one
+            (name_rva, if original_first_thunk != 0 { original_first_thunk } else { first_thunk }, first_thunk)
+        };
+        if name_rva == 0 {
+            break;
+        }
+        let dll = get_pe_string(rva_data(sections, base_img, name_rva).unwrap_or(&[]), 0);
+        let mut i = 0;
+        loop {
+            let thunk = match rva_data(sections, base_img, try!(rva_offset(lookup, i * thunk_size))) {
+                Some(mut x) if pe64 => try!(read_u64!(x)),
+                Some(mut x) => try!(read_u32!(x)) as u64,
+                None => return Err(Box::new(Error::from("invalid import lookup table"))),
+            };
+            if thunk == 0 {
+                break;
+            }
+            let (name, hint, ordinal) = if thunk & ord_flag != 0 {
+                (None, 0, Some(thunk as u16))
+            } else {
+                let mut data = match rva_data(sections, base_img, thunk as u32 & 0x7fffffff) {
+                    Some(x) => x,
+                    None => return Err(Box::new(Error::from("import hint/name RVA not mapped by any section"))),
+                };
+                let hint = try!(read_u16!(data));
+                (Some(get_pe_string(data, 0)), hint, None)
+            };
+            ret.push(types::Import {
+                dll: dll.clone(),
+                name: name,
+                hint: hint,
+                ordinal: ordinal,
+                iat_address: base_img.wrapping_add(iat as u64 + i * thunk_size),
+                delay: delay,
+            });
+            i += 1;
+        }
+        desc = try!(rva_offset(desc, if delay { 32 } else { 20 }));
+    }
+    Ok(ret)
+}
+
+/// Read the COFF relocations of a section
+fn read_coff_relocs(file_data: &[u8], shdr: &types::SectionHeader)
+                    -> Result<Vec<types::Relocation>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if shdr.num_relocs == 0 {
+        return Ok(ret);
+    }
+    // Each relocation is 10 bytes
+    let data = file_data.get(shdr.reloc_ptr as usize..).unwrap_or(&[]);
+    if shdr.num_relocs as usize * 10 > data.len() {
+        return Err(Box::new(Error::from("relocations extend past the end of the file")));
+    }
+    let mut c = io::Cursor::new(data);
+    for _ in 0..shdr.num_relocs {
+        ret.push(types::Relocation {
+            virt_addr: try!(read_u32!(c)),
+            sym_index: try!(read_u32!(c)),
+            reloc_type: try!(read_u16!(c)),
+        });
+    }
+    Ok(ret)
+}
+
+/// Read the base relocation directory as format independent relocations
+fn read_base_relocs(sections: &[Section], base_img: u64, dir: types::DataDirectory)
+                    -> Result<Vec<::Relocation>, Box<error::Error>> {
+    let mut ret = Vec::new();
+    if dir.virt_addr == 0 {
+        return Ok(ret);
+    }
+    let end = try!(rva_offset(dir.virt_addr, dir.size as u64));
+    let mut block = dir.virt_addr;
+    while end - block >= 8 {
+        let page = try!(rva_u32(sections, base_img, block));
+        let size = try!(rva_u32(sections, base_img, try!(rva_offset(block, 4))));
+        if size < 8 {
+            break;
+        }
+        let mut entries = match rva_data(sections, base_img, try!(rva_offset(block, 8))) {
+            Some(x) => io::Cursor::new(x),
+            None => return Err(Box::new(Error::from("invalid base relocation block"))),
+        };
+        let mut i = 0;
+        while i < (size - 8) / 2 {
+            let entry = try!(read_u16!(entries));
+            i += 1;
+            let reloc_type = entry >> 12;
+            if reloc_type == types::IMAGE_REL_BASED_ABSOLUTE {
+                continue;
+            }
+            if reloc_type == types::IMAGE_REL_BASED_HIGHADJ {
+                try!(read_u16!(entries));
+                i += 1;
+            }
+            let address = base_img.wrapping_add(page as u64 + (entry & 0xfff) as u64);
+            ret.push(::Relocation {
+                address: address,
+                section: sections.iter()
+                    .find(|x| address >= x.addr && address - x.addr < x.size)
+                    .map(|x| x.name.clone()),
+                kind: ::RelocationKind::BaseRelative,
+                raw_type: reloc_type as u32,
+                symbol: None,
+                addend: 0,
+            });
+        }
+        block = match block.checked_add(size) {
+            Some(x) if x <= end => x,
+            _ => break,
+        };
+    }
+    Ok(ret)
+}
+
+/// Convert the COFF symbols and exports to the format independent representation
+fn common_symbols(symtab: &[types::Symbol], exports: &[types::Export],
+                  section_hdrs: &[types::SectionHeader]) -> Vec<::Symbol> {
+    let section_name = |x: &types::SectionHeader| String::from_utf8_lossy(x.name.as_bytes()).into_owned();
+    let section_kind = |x: &types::SectionHeader| if x.characteristics & (types::IMAGE_SCN_CNT_CODE | types::IMAGE_SCN_MEM_EXECUTE) != 0 {
+        ::SymbolKind::Text
+    } else {
+        ::SymbolKind::Data
+    };
+    let mut ret = Vec::new();
+    for sym in symtab.iter() {
+        let sect_no = sym.section_number();
+        if sect_no == types::IMAGE_SYM_DEBUG {
+            continue;
+        }
+        let shdr = if sect_no > 0 { section_hdrs.get(sect_no as usize - 1) } else { None };
+        let is_common = sect_no == types::IMAGE_SYM_UNDEFINED && sym.value != 0 &&
+                        sym.storage_class == types::IMAGE_SYM_CLASS_EXTERNAL;
+        let kind = match sym.storage_class {
+            _ if is_common => ::SymbolKind::Common,
+            types::IMAGE_SYM_CLASS_FILE => ::SymbolKind::File,
+            types::IMAGE_SYM_CLASS_SECTION => ::SymbolKind::Section,
+            // Section definitions carry an auxiliary record and no value
+            types::IMAGE_SYM_CLASS_STATIC if sym.num_aux_sym > 0 && sym.value == 0 => ::SymbolKind::Section,
+            _ if sym.is_function() => ::SymbolKind::Text,
+            _ => shdr.map(|x| section_kind(x)).unwrap_or(::SymbolKind::Unknown),
+        };
+        let binding = match sym.storage_class {
+            types::IMAGE_SYM_CLASS_EXTERNAL => ::SymbolBinding::Global,
+            types::IMAGE_SYM_CLASS_WEAK_EXTERNAL => ::SymbolBinding::Weak,
+            _ => ::SymbolBinding::Local,
+        };
+        ret.push(::Symbol {
+            name: String::from_utf8_lossy(sym.name.as_bytes()).into_owned(),
+            address: shdr.map(|x| x.virt_addr).unwrap_or(0) + sym.value as u64,
+            size: 0,
+            kind: kind,
+            binding: binding,
+            visibility: ::SymbolVisibility::Default,
+            defined: sect_no != types::IMAGE_SYM_UNDEFINED,
+            section: shdr.map(|x| section_name(x)),
+        });
+    }
+    for export in exports.iter().filter(|x| x.forwarder.is_none()) {
+        let name = match export.name {
+            Some(ref x) => x.clone(),
+            None => format!("#{}", export.ordinal),
+        };
+        if ret.iter().any(|x| x.name == name && x.address == export.address) {
+            continue;
+        }
+        let shdr = section_hdrs.iter()
+            .find(|x| export.address >= x.virt_addr && export.address - x.virt_addr < x.virt_size as u64);
+        ret.push(::Symbol {
+            name: name,
+            address: export.address,
+            size: 0,
+            kind: shdr.map(|x| section_kind(x)).unwrap_or(::SymbolKind::Unknown),
+            binding: ::SymbolBinding::Global,
+            visibility: ::SymbolVisibility::Default,
+            defined: true,
+            section: shdr.map(|x| section_name(x)),
+        });
+    }
+    ret
+}
+
 pub struct File {
     pub file_hdr: types::FileHeader,
     pub opt_hdr: types::OptionalHeader,
-    pub sections: HashMap<String, Section>,
+    /// Sections in section table order
+    pub sections: Vec<Section>,
+    /// Section headers in file order; symbol section numbers index this
+    pub section_hdrs: Vec<types::SectionHeader>,
+    pub data_dirs: Vec<types::DataDirectory>,
+    /// COFF symbol table, usually only present in object files
+    pub symtab: Vec<types::Symbol>,
+    pub exports: Vec<types::Export>,
+    /// Import and delay import directory entries
+    pub imports: Vec<types::Import>,
+    /// COFF relocations of each section, parallel to `section_hdrs`
+    pub section_relocs: Vec<Vec<types::Relocation>>,
+    common_symbols: Vec<::Symbol>,
+    segments: Vec<::Segment>,
+    libraries: Vec<String>,
+    common_imports: Vec<::Import>,
+    common_exports: Vec<::Export>,
+    relocations: Vec<::Relocation>,
+    errors: Vec<Error>,
 }
 
 impl File {
@@ -140,10 +493,18 @@ impl File {
         let loader_flags = try!(read_u32!(r));
         let num_rva = try!(read_u32!(r));
 
+        let mut data_dirs = Vec::new();
+        for _ in 0..num_rva.min(16) {
+            data_dirs.push(types::DataDirectory {
+                virt_addr: try!(read_u32!(r)),
+                size: try!(read_u32!(r)),
+            });
+        }
+
         try!(r.seek(io::SeekFrom::Start((foff as u64+opt_hdr_size as u64+0x18))));
 
         let mut sections_lst = Vec::new();
-        let mut sections = HashMap::new();
+        let mut sections = Vec::new();
 
         for _ in 0..num_sections {
             let mut name = [0u8; 8];
@@ -181,19 +542,147 @@ impl File {
             });
         }
 
-        for shdr in sections_lst.into_iter() {
-            try!(r.seek(io::SeekFrom::Start(shdr.raw_ptr as u64)));
-            let data: Vec<u8> = io::Read::by_ref(r).bytes().map(|x| x.unwrap()).take(shdr.virt_size as usize).collect();
+        let file_data = try!(read_file(r));
+        for shdr in sections_lst.iter() {
+            let data = FileData::new(&file_data, shdr.raw_ptr as u64, shdr.virt_size as u64);
             let name = String::from_utf8(shdr.name.as_bytes().to_vec()).unwrap();
-            sections.insert(name.clone(), Section {
-                name: name,
+            let flags = shdr.characteristics;
+            // PE has no flags for debugging information or TLS data, so
+            // these go by the names the linkers use
+            let debug = name.starts_with(".debug");
+            // Alignment flags are only meaningful in object files; image
+            // sections are aligned to the section alignment
+            let align = match (flags & types::IMAGE_SCN_ALIGN_MASK) >> 20 {
+                0 if characteristics & types::IMAGE_FILE_EXECUTABLE_IMAGE != 0 => align_sec as u64,
+                0 => 16,
+                x => 1 << (x - 1),
+            };
+            sections.push(Section {
                 addr: shdr.virt_addr,
                 offset: shdr.virt_addr - base_img,
                 size: shdr.virt_size as u64,
                 data: data,
+                alloc: flags & (types::IMAGE_SCN_LNK_INFO | types::IMAGE_SCN_LNK_REMOVE) == 0 && !debug,
+                write: flags & types::IMAGE_SCN_MEM_WRITE != 0,
+                execute: flags & (types::IMAGE_SCN_MEM_EXECUTE | types::IMAGE_SCN_CNT_CODE) != 0,
+                bss: flags & types::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0,
+                tls: name == ".tls" || name.starts_with(".tls$"),
+                debug: debug,
+                align: align,
+                header: Some(::SectionHeader::Pe(shdr.clone())),
+                name: name,
             });
         }
 
+        // The headers are mapped read-only at the image base, followed by
+        // each section padded to the section alignment
+        let align = |x: u64| if align_sec == 0 { x } else { (x + align_sec as u64 - 1) / align_sec as u64 * align_sec as u64 };
+        let mut segments = Vec::new();
+        segments.push(::Segment {
+            name: None,
+            addr: base_img,
+            size: align(hdr_size as u64),
+            offset: 0,
+            file_size: hdr_size as u64,
+            read: true,
+            write: false,
+            execute: false,
+            data: FileData::new(&file_data, 0, hdr_size as u64),
+        });
+        for shdr in sections_lst.iter() {
+            // Raw data is padded to the file alignment, so it may exceed the
+            // virtual size
+            let file_size = (shdr.data_size as u64).min(shdr.virt_size as u64);
+            segments.push(::Segment {
+                name: Some(String::from_utf8_lossy(shdr.name.as_bytes()).into_owned()),
+                addr: shdr.virt_addr,
+                size: align(shdr.virt_size as u64),
+                offset: shdr.raw_ptr as u64,
+                file_size: file_size,
+                read: shdr.characteristics & types::IMAGE_SCN_MEM_READ != 0,
+                write: shdr.characteristics & types::IMAGE_SCN_MEM_WRITE != 0,
+                execute: shdr.characteristics & types::IMAGE_SCN_MEM_EXECUTE != 0,
+                data: FileData::new(&file_data, shdr.raw_ptr as u64, file_size),
+            });
+        }
+
+        let mut errors = Vec::new();
+        // Linked images often carry a stale symbol table pointer
+        let symtab = non_fatal(&mut errors, "COFF symbol table", read_coff_symbols(r, sym_tab_ptr, num_sym));
+        let exports = match data_dirs.get(types::IMAGE_DIRECTORY_ENTRY_EXPORT) {
+            Some(dir) => non_fatal(&mut errors, "export directory", read_exports(&sections, base_img, *dir)),
+            None => Vec::new(),
+        };
+        let common_symbols = common_symbols(&symtab, &exports, &sections_lst);
+
+        let mut section_relocs = Vec::new();
+        for shdr in sections_lst.iter() {
+            let what = format!("relocations of {}", String::from_utf8_lossy(shdr.name.as_bytes()));
+            section_relocs.push(non_fatal(&mut errors, &what, read_coff_relocs(&file_data, shdr)));
+        }
+
+        // Relocations count auxiliary records when indexing the symbol table
+        let mut raw_syms = HashMap::new();
+        let mut index = 0;
+        for sym in symtab.iter() {
+            raw_syms.insert(index, sym);
+            index += 1 + sym.num_aux_sym as u32;
+        }
+        let mut relocations = Vec::new();
+        for (shdr, relocs) in sections_lst.iter().zip(section_relocs.iter()) {
+            for reloc in relocs.iter() {
+                relocations.push(::Relocation {
+                    address: shdr.virt_addr.wrapping_add(reloc.virt_addr as u64),
+                    section: Some(String::from_utf8_lossy(shdr.name.as_bytes()).into_owned()),
+                    kind: types::relocation_kind(machine, reloc.reloc_type),
+                    raw_type: reloc.reloc_type as u32,
+                    symbol: raw_syms.get(&reloc.sym_index).map(|x| String::from_utf8_lossy(x.name.as_bytes()).into_owned()),
+                    addend: 0,
+                });
+            }
+        }
+        if let Some(dir) = data_dirs.get(types::IMAGE_DIRECTORY_ENTRY_BASERELOC) {
+            relocations.extend(non_fatal(&mut errors, "base relocation directory",
+                                         read_base_relocs(&sections, base_img, *dir)));
+        }
+
+        let pe64 = magic == types::PECLASS64;
+        let mut imports = Vec::new();
+        let dirs = [(types::IMAGE_DIRECTORY_ENTRY_IMPORT, false), (types::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT, true)];
+        for &(index, delay) in dirs.iter() {
+            if let Some(dir) = data_dirs.get(index) {
+                let what = if delay { "delay import directory" } else { "import directory" };
+                imports.extend(non_fatal(&mut errors, what, read_imports(&sections, base_img, pe64, *dir, delay)));
+            }
+        }
+        let mut libraries: Vec<String> = Vec::new();
+        for import in imports.iter() {
+            if !libraries.iter().any(|x| x.eq_ignore_ascii_case(&import.dll)) {
+                libraries.push(import.dll.clone());
+            }
+        }
+        let common_imports = imports.iter()
+            .map(|x| ::Import {
+                library: Some(x.dll.clone()),
+                name: x.name.clone(),
+                ordinal: x.ordinal.map(|x| x as u32),
+                version: None,
+                weak: false,
+                delay: x.delay,
+                address: Some(x.iat_address),
+            })
+            .collect();
+        let common_exports = exports.iter()
+            .map(|x| ::Export {
+                name: x.name.clone(),
+                ordinal: Some(x.ordinal),
+                address: if x.forwarder.is_some() { 0 } else { x.address },
+                version: None,
+                weak: false,
+                forwarder: x.forwarder.clone(),
+            })
+            .collect();
+
         Ok(File {
             file_hdr: types::FileHeader {
                 machine: machine,
@@ -237,12 +726,30 @@ impl File {
                 num_rva: num_rva,
             },
             sections: sections,
+            section_hdrs: sections_lst,
+            data_dirs: data_dirs,
+            symtab: symtab,
+            exports: exports,
+            imports: imports,
+            section_relocs: section_relocs,
+            common_symbols: common_symbols,
+            segments: segments,
+            libraries: libraries,
+            common_imports: common_imports,
+            common_exports: common_exports,
+            relocations: relocations,
+            errors: errors,
         })
     }
 
-    pub fn sections(&self) -> &HashMap<String, Section> {
+    pub fn sections(&self) -> &[Section] {
         &self.sections
     }
+    /// Errors from decoding the data directories. Accessors for a directory
+    /// that failed to decode return nothing for it.
+    pub fn decode_errors(&self) -> &[Error] {
+        &self.errors
+    }
 }
 
 impl fmt::Display for File {
@@ -262,6 +769,49 @@ impl Object for File {
         }
     }
     fn get_section(&self, name: &str) -> Option<&Section> {
-        self.sections.get(name)
+        self.sections.iter().find(|x| x.name == name)
+    }
+    fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+    fn symbols(&self) -> &[::Symbol] {
+        &self.common_symbols
+    }
+    fn segments(&self) -> &[::Segment] {
+        &self.segments
+    }
+    fn entry(&self) -> Option<u64> {
+        match self.opt_hdr.enter_addr {
+            0 => None,
+            x => Some(self.opt_hdr.base_img + x as u64),
+        }
+    }
+    fn image_base(&self) -> u64 {
+        self.opt_hdr.base_img
+    }
+    fn kind(&self) -> ::FileKind {
+        if self.file_hdr.characteristics & types::IMAGE_FILE_DLL != 0 {
+            ::FileKind::SharedLibrary
+        } else if self.file_hdr.characteristics & types::IMAGE_FILE_EXECUTABLE_IMAGE != 0 {
+            ::FileKind::Executable
+        } else {
+            ::FileKind::Relocatable
+        }
+    }
+    fn is_position_independent(&self) -> bool {
+        self.opt_hdr.dll_char & types::IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE != 0 &&
+        self.file_hdr.characteristics & types::IMAGE_FILE_RELOCS_STRIPPED == 0
+    }
+    fn libraries(&self) -> &[String] {
+        &self.libraries
+    }
+    fn imports(&self) -> &[::Import] {
+        &self.common_imports
+    }
+    fn exports(&self) -> &[::Export] {
+        &self.common_exports
+    }
+    fn relocations(&self) -> &[::Relocation] {
+        &self.relocations
     }
 }
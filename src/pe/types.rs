@@ -112,6 +112,28 @@ impl fmt::Display for Machine {
 pub const DOS_HDR_MAG: u16 = 0x5A4D;
 pub const PE_HDR_MAG: u32 = 0x00004550;
 
+/// Base relocations have been removed; the image must load at its base
+pub const IMAGE_FILE_RELOCS_STRIPPED: u16 = 0x0001;
+/// The image is valid and can be run
+pub const IMAGE_FILE_EXECUTABLE_IMAGE: u16 = 0x0002;
+/// The image can handle addresses above 2 GB
+pub const IMAGE_FILE_LARGE_ADDRESS_AWARE: u16 = 0x0020;
+/// The image is a DLL
+pub const IMAGE_FILE_DLL: u16 = 0x2000;
+
+/// The image can use high entropy 64-bit ASLR
+pub const IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA: u16 = 0x0020;
+/// The image can be relocated at load time (ASLR)
+pub const IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE: u16 = 0x0040;
+/// Code integrity checks are enforced
+pub const IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY: u16 = 0x0080;
+/// The image is compatible with data execution prevention
+pub const IMAGE_DLLCHARACTERISTICS_NX_COMPAT: u16 = 0x0100;
+/// The image does not use structured exception handling
+pub const IMAGE_DLLCHARACTERISTICS_NO_SEH: u16 = 0x0400;
+/// The image supports Control Flow Guard
+pub const IMAGE_DLLCHARACTERISTICS_GUARD_CF: u16 = 0x4000;
+
 pub struct FileHeader {
     pub machine: Machine,
     pub num_sections: u16,
@@ -155,6 +177,7 @@ pub struct OptionalHeader {
     pub num_rva: u32,
 }
 
+#[derive(Clone, Debug)]
 pub struct SectionHeader {
     pub name: ::std::ffi::CString,
     pub virt_size: u32,
@@ -168,6 +191,69 @@ pub struct SectionHeader {
     pub characteristics: u32,
 }
 
+/// Data directory entry of the optional header
+#[derive(Clone, Copy, Debug)]
+pub struct DataDirectory {
+    pub virt_addr: u32,
+    pub size: u32,
+}
+
+pub const IMAGE_DIRECTORY_ENTRY_EXPORT: usize = 0;
+pub const IMAGE_DIRECTORY_ENTRY_IMPORT: usize = 1;
+pub const IMAGE_DIRECTORY_ENTRY_RESOURCE: usize = 2;
+pub const IMAGE_DIRECTORY_ENTRY_EXCEPTION: usize = 3;
+pub const IMAGE_DIRECTORY_ENTRY_SECURITY: usize = 4;
+pub const IMAGE_DIRECTORY_ENTRY_BASERELOC: usize = 5;
+pub const IMAGE_DIRECTORY_ENTRY_DEBUG: usize = 6;
+pub const IMAGE_DIRECTORY_ENTRY_ARCHITECTURE: usize = 7;
+pub const IMAGE_DIRECTORY_ENTRY_GLOBALPTR: usize = 8;
+pub const IMAGE_DIRECTORY_ENTRY_TLS: usize = 9;
+pub const IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG: usize = 10;
+pub const IMAGE_DIRECTORY_ENTRY_BOUND_IMPORT: usize = 11;
+pub const IMAGE_DIRECTORY_ENTRY_IAT: usize = 12;
+pub const IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT: usize = 13;
+pub const IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR: usize = 14;
+
+/// Section contains executable code
+pub const IMAGE_SCN_CNT_CODE: u32 = 0x00000020;
+/// Section contains initialized data
+pub const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x00000040;
+/// Section contains uninitialized data
+pub const IMAGE_SCN_CNT_UNINITIALIZED_DATA: u32 = 0x00000080;
+/// Section contains comments or other information (object files only)
+pub const IMAGE_SCN_LNK_INFO: u32 = 0x00000200;
+/// Section is not part of the image (object files only)
+pub const IMAGE_SCN_LNK_REMOVE: u32 = 0x00000800;
+/// Mask for the alignment of object file sections, log2 plus one
+pub const IMAGE_SCN_ALIGN_MASK: u32 = 0x00f00000;
+/// Section can be discarded after loading
+pub const IMAGE_SCN_MEM_DISCARDABLE: u32 = 0x02000000;
+/// Section can be executed as code
+pub const IMAGE_SCN_MEM_EXECUTE: u32 = 0x20000000;
+/// Section can be read
+pub const IMAGE_SCN_MEM_READ: u32 = 0x40000000;
+/// Section can be written to
+pub const IMAGE_SCN_MEM_WRITE: u32 = 0x80000000;
+
+/// Symbol section number: external symbol, or common if the value is non-zero
+pub const IMAGE_SYM_UNDEFINED: i16 = 0;
+/// Symbol section number: absolute value
+pub const IMAGE_SYM_ABSOLUTE: i16 = -1;
+/// Symbol section number: debugging symbol
+pub const IMAGE_SYM_DEBUG: i16 = -2;
+
+/// Derived symbol type: function
+pub const IMAGE_SYM_DTYPE_FUNCTION: u16 = 2;
+
+pub const IMAGE_SYM_CLASS_EXTERNAL: u8 = 2;
+pub const IMAGE_SYM_CLASS_STATIC: u8 = 3;
+pub const IMAGE_SYM_CLASS_LABEL: u8 = 6;
+pub const IMAGE_SYM_CLASS_FUNCTION: u8 = 101;
+pub const IMAGE_SYM_CLASS_FILE: u8 = 103;
+pub const IMAGE_SYM_CLASS_SECTION: u8 = 104;
+pub const IMAGE_SYM_CLASS_WEAK_EXTERNAL: u8 = 105;
+
+/// COFF symbol table entry; auxiliary records are skipped
 pub struct Symbol {
     pub name: ::std::ffi::CString,
     pub value: u32,
@@ -182,3 +268,139 @@ impl SectionHeader {
         self.virt_addr as u64
     }
 }
+
+impl Symbol {
+    /// Section number, or one of the IMAGE_SYM_* special values
+    pub fn section_number(&self) -> i16 {
+        self.sect_no as i16
+    }
+    pub fn is_function(&self) -> bool {
+        (self.sym_type >> 4) & 0x3 == IMAGE_SYM_DTYPE_FUNCTION
+    }
+}
+
+/// Export directory entry
+#[derive(Debug)]
+pub struct Export {
+    /// Exported name; ordinal-only exports have none
+    pub name: Option<String>,
+    pub ordinal: u32,
+    pub rva: u32,
+    /// Virtual address, including the image base
+    pub address: u64,
+    /// "DLL.Function" or "DLL.#ordinal" for forwarded exports
+    pub forwarder: Option<String>,
+}
+
+/// Delay import descriptor attribute: fields are RVAs rather than addresses
+pub const DELAYLOAD_RVA_BASED: u32 = 0x1;
+
+/// Import directory or delay import directory entry
+#[derive(Debug)]
+pub struct Import {
+    pub dll: String,
+    /// Imported name; imports by ordinal have none
+    pub name: Option<String>,
+    /// Index into the DLL's export name table to try first
+    pub hint: u16,
+    pub ordinal: Option<u16>,
+    /// Virtual address of the import address table slot
+    pub iat_address: u64,
+    pub delay: bool,
+}
+
+pub const IMAGE_REL_AMD64_ABSOLUTE: u16 = 0x0;
+pub const IMAGE_REL_AMD64_ADDR64: u16 = 0x1;
+pub const IMAGE_REL_AMD64_ADDR32: u16 = 0x2;
+pub const IMAGE_REL_AMD64_ADDR32NB: u16 = 0x3;
+pub const IMAGE_REL_AMD64_REL32: u16 = 0x4;
+pub const IMAGE_REL_AMD64_REL32_5: u16 = 0x9;
+pub const IMAGE_REL_AMD64_SECTION: u16 = 0xa;
+pub const IMAGE_REL_AMD64_SECREL: u16 = 0xb;
+
+pub const IMAGE_REL_I386_ABSOLUTE: u16 = 0x0;
+pub const IMAGE_REL_I386_DIR16: u16 = 0x1;
+pub const IMAGE_REL_I386_REL16: u16 = 0x2;
+pub const IMAGE_REL_I386_DIR32: u16 = 0x6;
+pub const IMAGE_REL_I386_DIR32NB: u16 = 0x7;
+pub const IMAGE_REL_I386_SECTION: u16 = 0xa;
+pub const IMAGE_REL_I386_SECREL: u16 = 0xb;
+pub const IMAGE_REL_I386_REL32: u16 = 0x14;
+
+pub const IMAGE_REL_ARM_ABSOLUTE: u16 = 0x0;
+pub const IMAGE_REL_ARM_ADDR32: u16 = 0x1;
+pub const IMAGE_REL_ARM_ADDR32NB: u16 = 0x2;
+pub const IMAGE_REL_ARM_BRANCH24: u16 = 0x3;
+pub const IMAGE_REL_ARM_BRANCH11: u16 = 0x4;
+pub const IMAGE_REL_ARM_REL32: u16 = 0xa;
+pub const IMAGE_REL_ARM_MOV32: u16 = 0x10;
+pub const IMAGE_REL_THUMB_MOV32: u16 = 0x11;
+pub const IMAGE_REL_THUMB_BRANCH20: u16 = 0x12;
+pub const IMAGE_REL_THUMB_BRANCH24: u16 = 0x14;
+pub const IMAGE_REL_THUMB_BLX23: u16 = 0x15;
+
+pub const IMAGE_REL_ARM64_ABSOLUTE: u16 = 0x0;
+pub const IMAGE_REL_ARM64_ADDR32: u16 = 0x1;
+pub const IMAGE_REL_ARM64_ADDR32NB: u16 = 0x2;
+pub const IMAGE_REL_ARM64_BRANCH26: u16 = 0x3;
+pub const IMAGE_REL_ARM64_PAGEBASE_REL21: u16 = 0x4;
+pub const IMAGE_REL_ARM64_REL21: u16 = 0x5;
+pub const IMAGE_REL_ARM64_PAGEOFFSET_12A: u16 = 0x6;
+pub const IMAGE_REL_ARM64_PAGEOFFSET_12L: u16 = 0x7;
+pub const IMAGE_REL_ARM64_ADDR64: u16 = 0xe;
+pub const IMAGE_REL_ARM64_BRANCH19: u16 = 0xf;
+pub const IMAGE_REL_ARM64_BRANCH14: u16 = 0x10;
+pub const IMAGE_REL_ARM64_REL32: u16 = 0x11;
+
+/// Base relocation padding entry, skipped by the loader
+pub const IMAGE_REL_BASED_ABSOLUTE: u16 = 0;
+pub const IMAGE_REL_BASED_HIGH: u16 = 1;
+pub const IMAGE_REL_BASED_LOW: u16 = 2;
+pub const IMAGE_REL_BASED_HIGHLOW: u16 = 3;
+/// Takes the low half of the adjustment from the following entry
+pub const IMAGE_REL_BASED_HIGHADJ: u16 = 4;
+pub const IMAGE_REL_BASED_DIR64: u16 = 10;
+
+/// COFF relocation entry
+#[derive(Debug)]
+pub struct Relocation {
+    /// Offset of the fixed up location from the start of the section
+    pub virt_addr: u32,
+    /// Raw symbol table index, counting auxiliary records
+    pub sym_index: u32,
+    pub reloc_type: u16,
+}
+
+/// Map a COFF relocation type of the given machine to its portable category
+pub fn relocation_kind(machine: Machine, reloc_type: u16) -> ::RelocationKind {
+    match machine {
+        PM_AMD6 => match reloc_type {
+            IMAGE_REL_AMD64_ADDR64 | IMAGE_REL_AMD64_ADDR32 => ::RelocationKind::Absolute,
+            IMAGE_REL_AMD64_ADDR32NB => ::RelocationKind::ImageRelative,
+            IMAGE_REL_AMD64_REL32 ..= IMAGE_REL_AMD64_REL32_5 => ::RelocationKind::PcRelative,
+            _ => ::RelocationKind::Other,
+        },
+        PM_I386 => match reloc_type {
+            IMAGE_REL_I386_DIR16 | IMAGE_REL_I386_DIR32 => ::RelocationKind::Absolute,
+            IMAGE_REL_I386_DIR32NB => ::RelocationKind::ImageRelative,
+            IMAGE_REL_I386_REL16 | IMAGE_REL_I386_REL32 => ::RelocationKind::PcRelative,
+            _ => ::RelocationKind::Other,
+        },
+        PM_ARM | PM_ARMNT => match reloc_type {
+            IMAGE_REL_ARM_ADDR32 | IMAGE_REL_ARM_MOV32 | IMAGE_REL_THUMB_MOV32 => ::RelocationKind::Absolute,
+            IMAGE_REL_ARM_ADDR32NB => ::RelocationKind::ImageRelative,
+            IMAGE_REL_ARM_BRANCH24 | IMAGE_REL_ARM_BRANCH11 | IMAGE_REL_ARM_REL32 | IMAGE_REL_THUMB_BRANCH20 |
+            IMAGE_REL_THUMB_BRANCH24 | IMAGE_REL_THUMB_BLX23 => ::RelocationKind::PcRelative,
+            _ => ::RelocationKind::Other,
+        },
+        PM_ARM64 => match reloc_type {
+            IMAGE_REL_ARM64_ADDR32 | IMAGE_REL_ARM64_ADDR64 | IMAGE_REL_ARM64_PAGEOFFSET_12A |
+            IMAGE_REL_ARM64_PAGEOFFSET_12L => ::RelocationKind::Absolute,
+            IMAGE_REL_ARM64_ADDR32NB => ::RelocationKind::ImageRelative,
+            IMAGE_REL_ARM64_BRANCH26 | IMAGE_REL_ARM64_PAGEBASE_REL21 | IMAGE_REL_ARM64_REL21 |
+            IMAGE_REL_ARM64_BRANCH19 | IMAGE_REL_ARM64_BRANCH14 | IMAGE_REL_ARM64_REL32 => ::RelocationKind::PcRelative,
+            _ => ::RelocationKind::Other,
+        },
+        _ => ::RelocationKind::Other,
+    }
+}
@@ -0,0 +1,412 @@
+//! Swift reflection metadata, shared by the Mach-O (`__swift5_*`) and ELF
+//! (`swift5_*`) parsers. Swift only targets little endian platforms, so all
+//! structures are read little endian.
+
+use std::io;
+use std::error;
+use std::collections::HashMap;
+use byteorder;
+use byteorder::ReadBytesExt;
+use Error;
+
+pub const CONTEXT_KIND_MODULE: u8 = 0;
+pub const CONTEXT_KIND_EXTENSION: u8 = 1;
+pub const CONTEXT_KIND_ANONYMOUS: u8 = 2;
+pub const CONTEXT_KIND_PROTOCOL: u8 = 3;
+pub const CONTEXT_KIND_OPAQUE_TYPE: u8 = 4;
+pub const CONTEXT_KIND_CLASS: u8 = 16;
+pub const CONTEXT_KIND_STRUCT: u8 = 17;
+pub const CONTEXT_KIND_ENUM: u8 = 18;
+
+/// Context descriptor flag: the context has generic parameters
+pub const CONTEXT_IS_GENERIC: u32 = 0x80;
+/// Context descriptor flag: the context is uniqued
+pub const CONTEXT_IS_UNIQUE: u32 = 0x40;
+
+pub const FIELD_DESCRIPTOR_STRUCT: u16 = 0;
+pub const FIELD_DESCRIPTOR_CLASS: u16 = 1;
+pub const FIELD_DESCRIPTOR_ENUM: u16 = 2;
+pub const FIELD_DESCRIPTOR_MULTI_PAYLOAD_ENUM: u16 = 3;
+pub const FIELD_DESCRIPTOR_PROTOCOL: u16 = 4;
+pub const FIELD_DESCRIPTOR_CLASS_PROTOCOL: u16 = 5;
+pub const FIELD_DESCRIPTOR_OBJC_PROTOCOL: u16 = 6;
+pub const FIELD_DESCRIPTOR_OBJC_CLASS: u16 = 7;
+
+/// Field record flag: indirect enum case
+pub const FIELD_IS_INDIRECT_CASE: u32 = 0x1;
+/// Field record flag: mutable (`var`) stored property
+pub const FIELD_IS_VAR: u32 = 0x2;
+/// Field record flag: compiler generated field
+pub const FIELD_IS_ARTIFICIAL: u32 = 0x4;
+
+/// Stored property or enum case of a nominal type
+#[derive(Debug)]
+pub struct Field {
+    pub name: String,
+    /// Mangled type name; symbolic references to context descriptors in this
+    /// image are replaced by the descriptor's qualified name, others by
+    /// `<symbolic 0x...>`. Empty for enum cases without a payload.
+    pub mangled_type_name: String,
+    pub flags: u32,
+}
+
+impl Field {
+    pub fn is_var(&self) -> bool {
+        self.flags & FIELD_IS_VAR != 0
+    }
+    pub fn is_indirect_case(&self) -> bool {
+        self.flags & FIELD_IS_INDIRECT_CASE != 0
+    }
+}
+
+/// Nominal type (class, struct or enum) context descriptor
+#[derive(Debug)]
+pub struct Type {
+    /// Address of the context descriptor
+    pub address: u64,
+    pub flags: u32,
+    pub name: String,
+    /// Qualified name of the enclosing context, e.g. the module
+    pub parent: Option<String>,
+    /// Mangled superclass name of a class
+    pub superclass: Option<String>,
+    pub fields: Vec<Field>,
+}
+
+impl Type {
+    /// One of the CONTEXT_KIND_* constants
+    pub fn kind(&self) -> u8 {
+        (self.flags & 0x1f) as u8
+    }
+    pub fn is_generic(&self) -> bool {
+        self.flags & CONTEXT_IS_GENERIC != 0
+    }
+    /// Name including its parent contexts, e.g. "Module.Outer.Inner"
+    pub fn qualified_name(&self) -> String {
+        match self.parent {
+            Some(ref x) => format!("{}.{}", x, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Protocol context descriptor
+#[derive(Debug)]
+pub struct Protocol {
+    pub address: u64,
+    pub name: String,
+    pub parent: Option<String>,
+    pub num_requirements: u32,
+}
+
+/// Protocol conformance record
+#[derive(Debug)]
+pub struct Conformance {
+    pub address: u64,
+    /// Qualified protocol name, unless the protocol lives in another image
+    pub protocol: Option<String>,
+    /// Qualified name of the conforming type (or the Objective-C class name)
+    pub type_name: Option<String>,
+    pub flags: u32,
+}
+
+/// Swift reflection metadata of an image
+#[derive(Debug)]
+pub struct Metadata {
+    pub types: Vec<Type>,
+    pub protocols: Vec<Protocol>,
+    pub conformances: Vec<Conformance>,
+}
+
+/// Virtual memory view over the sections of an image
+struct Image<'a> {
+    sections: &'a [(&'a str, u64, &'a [u8])],
+    ptr_size: u64,
+}
+
+/// Target of a relative pointer at `addr` holding `offset`
+fn relative(addr: u64, offset: i32) -> Result<u64, Box<error::Error>> {
+    match addr.checked_add_signed(offset as i64) {
+        Some(x) => Ok(x),
+        None => Err(Box::new(Error::from("invalid relative pointer"))),
+    }
+}
+
+impl<'a> Image<'a> {
+    fn section(&self, name: &str) -> Option<(u64, &'a [u8])> {
+        self.sections.iter().find(|x| x.0 == name).map(|x| (x.1, x.2))
+    }
+
+    fn bytes(&self, addr: u64) -> Result<&'a [u8], Box<error::Error>> {
+        for &(_, start, data) in self.sections.iter() {
+            if addr >= start && addr - start < data.len() as u64 {
+                return Ok(&data[(addr - start) as usize..]);
+            }
+        }
+        Err(Box::new(Error::from("address not mapped by any section")))
+    }
+
+    fn u16(&self, addr: u64) -> Result<u16, Box<error::Error>> {
+        Ok(try!(io::Cursor::new(try!(self.bytes(addr))).read_u16::<byteorder::LittleEndian>()))
+    }
+
+    fn u32(&self, addr: u64) -> Result<u32, Box<error::Error>> {
+        Ok(try!(io::Cursor::new(try!(self.bytes(addr))).read_u32::<byteorder::LittleEndian>()))
+    }
+
+    fn ptr(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        let mut c = io::Cursor::new(try!(self.bytes(addr)));
+        if self.ptr_size == 4 {
+            Ok(try!(c.read_u32::<byteorder::LittleEndian>()) as u64)
+        } else {
+            Ok(try!(c.read_u64::<byteorder::LittleEndian>()))
+        }
+    }
+
+    /// Target of the 32-bit relative pointer at `addr`, or 0 if it is null
+    fn rel(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        let offset = try!(self.u32(addr)) as i32;
+        if offset == 0 {
+            Ok(0)
+        } else {
+            relative(addr, offset)
+        }
+    }
+
+    /// Relative pointer whose low bit marks an indirection through a
+    /// pointer slot. Returns 0 when the slot is not filled in the file,
+    /// as for symbols bound from other images.
+    fn rel_indirect(&self, addr: u64) -> Result<u64, Box<error::Error>> {
+        let offset = try!(self.u32(addr)) as i32;
+        if offset == 0 {
+            return Ok(0);
+        }
+        let target = try!(relative(addr, offset & !1));
+        if offset & 1 == 0 {
+            return Ok(target);
+        }
+        Ok(self.ptr(target).unwrap_or(0))
+    }
+
+    fn string(&self, addr: u64) -> Result<String, Box<error::Error>> {
+        let data = try!(self.bytes(addr));
+        let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+        Ok(String::from_utf8_lossy(&data[..end]).into_owned())
+    }
+
+    /// Mangled name at `addr` with symbolic references resolved. `depth`
+    /// counts the enclosing mangled names; references are only followed
+    /// from the first two, which covers extensions of types in this image
+    /// and stops a corrupt reference cycle from recursing forever.
+    fn mangled_name(&self, addr: u64, depth: u32) -> Result<String, Box<error::Error>> {
+        let data = try!(self.bytes(addr));
+        let mut ret = String::new();
+        let mut i = 0;
+        while i < data.len() && data[i] != 0 {
+            match data[i] {
+                0x01..=0x17 => {
+                    let field = addr.wrapping_add(i as u64 + 1);
+                    let target = try!(self.rel(field));
+                    let resolved = match data[i] {
+                        _ if depth >= 2 => None,
+                        0x01 => self.context_name(target, depth + 1).ok(),
+                        0x02 => self.context_name(self.ptr(target).unwrap_or(0), depth + 1).ok(),
+                        _ => None,
+                    };
+                    match resolved {
+                        Some(x) => ret.push_str(&x),
+                        None => ret.push_str(&format!("<symbolic {:#x}>", target)),
+                    }
+                    i += 5;
+                }
+                0x18..=0x1f => {
+                    ret.push_str(&format!("<symbolic {:#x}>", try!(self.ptr(addr.wrapping_add(i as u64 + 1)))));
+                    i += 1 + self.ptr_size as usize;
+                }
+                x => {
+                    ret.push(x as char);
+                    i += 1;
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Name of the context descriptor at `addr`
+    fn context_name_only(&self, addr: u64, depth: u32) -> Result<Option<String>, Box<error::Error>> {
+        let flags = try!(self.u32(addr));
+        match (flags & 0x1f) as u8 {
+            CONTEXT_KIND_MODULE | CONTEXT_KIND_PROTOCOL | CONTEXT_KIND_CLASS |
+            CONTEXT_KIND_STRUCT | CONTEXT_KIND_ENUM => {
+                Ok(Some(try!(self.string(try!(self.rel(addr.wrapping_add(8)))))))
+            }
+            CONTEXT_KIND_EXTENSION => {
+                let extended = try!(self.rel(addr.wrapping_add(8)));
+                Ok(Some(try!(self.mangled_name(extended, depth))))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Qualified name of the context descriptor at `addr`, walking its parents
+    fn context_name(&self, addr: u64, depth: u32) -> Result<String, Box<error::Error>> {
+        let mut parts = Vec::new();
+        let mut cur = addr;
+        // Bound the walk in case of a corrupt parent cycle
+        for _ in 0..64 {
+            if cur == 0 {
+                break;
+            }
+            let flags = try!(self.u32(cur));
+            // Extensions stand in for the extended type, whose own parents
+            // are already part of the mangled name
+            let is_extension = (flags & 0x1f) as u8 == CONTEXT_KIND_EXTENSION;
+            if let Some(x) = try!(self.context_name_only(cur, depth)) {
+                parts.push(x);
+            }
+            if is_extension {
+                break;
+            }
+            cur = try!(self.rel_indirect(cur.wrapping_add(4)));
+        }
+        if parts.is_empty() {
+            try!(Err(Error::from("unnamed context descriptor")));
+        }
+        parts.reverse();
+        Ok(parts.join("."))
+    }
+
+    fn parent_name(&self, addr: u64) -> Option<String> {
+        match self.rel_indirect(addr.wrapping_add(4)) {
+            Ok(0) | Err(_) => None,
+            Ok(x) => self.context_name(x, 0).ok(),
+        }
+    }
+
+    fn fields(&self, addr: u64) -> Result<(Option<String>, Vec<Field>), Box<error::Error>> {
+        let mut ret = Vec::new();
+        if addr == 0 {
+            return Ok((None, ret));
+        }
+        let superclass = match try!(self.rel(addr.wrapping_add(4))) {
+            0 => None,
+            x => Some(try!(self.mangled_name(x, 0))),
+        };
+        let record_size = try!(self.u16(addr.wrapping_add(10))) as u64;
+        let count = try!(self.u32(addr.wrapping_add(12))) as u64;
+        // Flags and the type name and field name offsets, and no more
+        // records than the section can hold
+        if record_size < 12 {
+            try!(Err(Error::from("invalid field record size")));
+        }
+        let records = addr.wrapping_add(16);
+        if count > self.bytes(records).map(|x| x.len() as u64).unwrap_or(0) / record_size {
+            try!(Err(Error::from("field records extend past their section")));
+        }
+        for i in 0..count {
+            let rec = records.wrapping_add(i * record_size);
+            let type_name = try!(self.rel(rec.wrapping_add(4)));
+            ret.push(Field {
+                flags: try!(self.u32(rec)),
+                mangled_type_name: if type_name == 0 { String::new() } else { try!(self.mangled_name(type_name, 0)) },
+                name: try!(self.string(try!(self.rel(rec.wrapping_add(8))))),
+            });
+        }
+        Ok((superclass, ret))
+    }
+
+    fn nominal_type(&self, addr: u64) -> Result<Type, Box<error::Error>> {
+        let (superclass, fields) = try!(self.fields(try!(self.rel(addr.wrapping_add(16)))));
+        Ok(Type {
+            address: addr,
+            flags: try!(self.u32(addr)),
+            name: try!(self.string(try!(self.rel(addr.wrapping_add(8))))),
+            parent: self.parent_name(addr),
+            superclass: superclass,
+            fields: fields,
+        })
+    }
+
+    /// Entries of a section holding an array of 32-bit relative pointers
+    fn rel_list(&self, name: &str) -> Vec<u64> {
+        match self.section(name) {
+            Some((start, data)) => (0..data.len() as u64 / 4).map(|i| start.wrapping_add(i * 4)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Decode the Swift metadata sections. `sections` lists every section of
+/// the image as (name, address, data); `prefix` is "__swift5_" for Mach-O and
+/// "swift5_" for ELF.
+pub fn parse_swift(sections: &[(&str, u64, &[u8])], prefix: &str, ptr_size: u64)
+                   -> Result<Metadata, Box<error::Error>> {
+    let image = Image {
+        sections: sections,
+        ptr_size: ptr_size,
+    };
+    let mut ret = Metadata {
+        types: Vec::new(),
+        protocols: Vec::new(),
+        conformances: Vec::new(),
+    };
+
+    let mut seen = HashMap::new();
+    for entry in image.rel_list(&format!("{}types", prefix)) {
+        // Low bits select a direct or indirect type descriptor reference
+        let offset = try!(image.u32(entry)) as i32;
+        let target = try!(relative(entry, offset & !3));
+        let addr = match offset & 3 {
+            0 => target,
+            1 => image.ptr(target).unwrap_or(0),
+            _ => continue,
+        };
+        if addr == 0 || seen.insert(addr, ()).is_some() {
+            continue;
+        }
+        let kind = (try!(image.u32(addr)) & 0x1f) as u8;
+        if kind == CONTEXT_KIND_CLASS || kind == CONTEXT_KIND_STRUCT || kind == CONTEXT_KIND_ENUM {
+            ret.types.push(try!(image.nominal_type(addr)));
+        }
+    }
+
+    for entry in image.rel_list(&format!("{}protos", prefix)) {
+        let addr = try!(image.rel_indirect(entry));
+        if addr == 0 {
+            continue;
+        }
+        ret.protocols.push(Protocol {
+            address: addr,
+            name: try!(image.string(try!(image.rel(addr.wrapping_add(8))))),
+            parent: image.parent_name(addr),
+            num_requirements: try!(image.u32(addr.wrapping_add(16))),
+        });
+    }
+
+    for entry in image.rel_list(&format!("{}proto", prefix)) {
+        let addr = try!(image.rel(entry));
+        if addr == 0 {
+            continue;
+        }
+        let flags = try!(image.u32(addr.wrapping_add(12)));
+        let protocol = match try!(image.rel_indirect(addr)) {
+            0 => None,
+            x => image.context_name(x, 0).ok(),
+        };
+        let type_name = match (flags >> 3) & 7 {
+            0 => image.rel(addr.wrapping_add(4)).ok().and_then(|x| image.context_name(x, 0).ok()),
+            1 => image.rel(addr.wrapping_add(4)).ok()
+                .and_then(|x| image.ptr(x).ok())
+                .and_then(|x| image.context_name(x, 0).ok()),
+            2 => image.rel(addr.wrapping_add(4)).ok().and_then(|x| image.string(x).ok()),
+            _ => None,
+        };
+        ret.conformances.push(Conformance {
+            address: addr,
+            protocol: protocol,
+            type_name: type_name,
+            flags: flags,
+        });
+    }
+    Ok(ret)
+}
@@ -0,0 +1,772 @@
+#!/usr/bin/env python3
+"""Regenerate the synthetic test fixtures in this directory.
+
+Every fixture is built byte by byte so the expected values in the tests can
+be read off the code below. Run from this directory: python3 mkfixtures.py
+"""
+import hashlib
+import struct
+
+E = '<'
+BASE = 0x100000000
+
+
+def align(data, n):
+    return data + b'\0' * ((n - len(data) % n) % n)
+
+
+def dylib(cmd, name):
+    name = align(name + b'\0', 8)
+    return struct.pack(E + 'IIIIII', cmd, 24 + len(name), 24, 2, 1 << 16, 1 << 16) + name
+
+
+def uleb(v):
+    out = b''
+    while True:
+        b = v & 0x7f
+        v >>= 7
+        if v:
+            out += bytes([b | 0x80])
+        else:
+            return out + bytes([b])
+
+
+def export_trie(exports):
+    """Serialize {name: terminal info} as a compressed prefix trie"""
+    root = {}
+    for name in exports:
+        node = root
+        for c in name:
+            node = node.setdefault(bytes([c]), {})
+        node[None] = exports[name]
+
+    def compress(node):
+        children = []
+        for label, child in sorted((k, v) for k, v in node.items() if k is not None):
+            while None not in child and len(child) == 1:
+                (more, child), = child.items()
+                label += more
+            children.append((label, compress(child)))
+        return (node.get(None), children)
+
+    # Lay the nodes out breadth first, growing the child offsets until stable
+    nodes = []
+    queue = [compress(root)]
+    while queue:
+        node = queue.pop(0)
+        nodes.append(node)
+        queue.extend(x[1] for x in node[1])
+    offsets = [0] * len(nodes)
+    while True:
+        out = b''
+        new = []
+        for term, children in nodes:
+            new.append(len(out))
+            out += uleb(len(term)) + term if term else b'\0'
+            out += bytes([len(children)])
+            for label, child in children:
+                index = next(i for i, x in enumerate(nodes) if x is child)
+                out += label + b'\0' + uleb(offsets[index])
+        if new == offsets:
+            return out
+        offsets = new
+
+
+class MachO:
+    """64-bit little endian image with __PAGEZERO, __TEXT (file 0x0, vm
+    BASE), __DATA (file 0x1000, vm BASE + 0x1000) and __LINKEDIT (file
+    0x2000, vm BASE + 0x2000). Sections are packed 16 byte aligned, __TEXT
+    ones from file offset 0x800."""
+
+    def __init__(self, cpu=0x01000007, sub=3, ftype=2):
+        self.cpu = cpu
+        self.sub = sub
+        self.ftype = ftype
+        self.text = b'\xc3' * 0x40
+        self.data = b'\0' * 0x40
+        # (segment, section, bytes, flags)
+        self.extra_sects = []
+        # section name: (reserved1, reserved2)
+        self.reserved = {}
+        # section name: (reloff, nreloc)
+        self.relocs = {}
+        self.cmds = []
+        self.linkedit = b''
+        self.addrs = {}
+
+    def segment(self, name, vm, off, sects, prot):
+        body = b''
+        cur = off + 0x800 if name == b'__TEXT' else off
+        for sect, data, flags in sects:
+            addr = vm + cur - off
+            self.addrs[sect] = addr
+            body += struct.pack(E + '16s16sQQIIIIIIII', sect, name, addr, len(data), cur, 4,
+                                *self.relocs.get(sect, (0, 0)), flags, *self.reserved.get(sect, (0, 0)), 0)
+            self.blobs.append((cur, data))
+            cur += (len(data) + 15) // 16 * 16
+        return struct.pack(E + 'II16sQQQQiiII', 0x19, 72 + 80 * len(sects), name, vm, 0x1000, off, 0x1000,
+                           prot, prot, len(sects), 0) + body
+
+    def build(self):
+        self.blobs = []
+        text = [(b'__text', self.text, 0x80000400)]
+        data = [(b'__data', self.data, 0)]
+        for seg, sect, blob, flags in self.extra_sects:
+            (text if seg == b'__TEXT' else data).append((sect, blob, flags))
+        cmds = [struct.pack(E + 'II16sQQQQiiII', 0x19, 72, b'__PAGEZERO', 0, BASE, 0, 0, 0, 0, 0, 0),
+                self.segment(b'__TEXT', BASE, 0, text, 5),
+                self.segment(b'__DATA', BASE + 0x1000, 0x1000, data, 3),
+                struct.pack(E + 'II16sQQQQiiII', 0x19, 72, b'__LINKEDIT', BASE + 0x2000, 0x1000, 0x2000,
+                            max(len(self.linkedit), 1), 1, 1, 0, 0)]
+        cmds += self.cmds
+        body = b''.join(cmds)
+        hdr = struct.pack(E + 'IiIIIIII', 0xfeedfacf, self.cpu, self.sub, self.ftype, len(cmds), len(body),
+                          0x200085, 0)
+        out = bytearray(hdr + body)
+        assert len(out) < 0x800
+        out += b'\0' * (0x2000 + len(self.linkedit) - len(out))
+        for off, blob in self.blobs:
+            out[off:off + len(blob)] = blob
+        out[0x2000:] = self.linkedit
+        return bytes(out)
+
+
+def chained_fixups(name, cpu, sub, pointer_format, ptrs, imports_format, imports, symbols_format=0):
+    """__DATA holds the chain `ptrs`, starting at its first byte"""
+    m = MachO(cpu=cpu, sub=sub)
+    m.data = b''.join(struct.pack('<Q', x) for x in ptrs)
+    symbols = b'\0_printf\0_objc_msgSend\0'
+    # dyld_chained_starts_in_image for four segments, only __DATA has fixups
+    seg = struct.pack('<IHHQIHH', 24, 0x4000, pointer_format, 0x1000, 0, 1, 0)
+    starts = align(struct.pack('<5I', 4, 0, 0, 20, 0) + seg, 4)
+    starts_off = 28
+    imports_off = starts_off + len(starts)
+    symbols_off = imports_off + len(imports)
+    blob = struct.pack('<7I', 0, starts_off, imports_off, symbols_off, 2, imports_format, symbols_format)
+    blob += starts + imports + symbols
+    m.linkedit = blob
+    m.cmds.append(dylib(0xc, b'/usr/lib/libSystem.B.dylib'))
+    m.cmds.append(dylib(0xc, b'/usr/lib/libobjc.A.dylib'))
+    m.cmds.append(struct.pack(E + 'IIII', 0x80000034, 16, 0x2000, len(blob)))
+    open(name, 'wb').write(m.build())
+
+
+def ptr64(target=0, high8=0, next=0, bind=False, ordinal=0, addend=0):
+    if bind:
+        return (1 << 63) | (next << 51) | (addend << 24) | ordinal
+    return (next << 51) | (high8 << 36) | target
+
+
+def arm64e(target=0, high8=0, next=0, auth=False, bind=False, ordinal=0, addend=0, diversity=0, addr_div=0,
+           key=0):
+    v = next << 51
+    if auth:
+        v |= (1 << 63) | (key << 49) | (addr_div << 48) | (diversity << 32)
+    if bind:
+        v |= (1 << 62) | ordinal
+        if not auth:
+            v |= (addend & 0x7ffff) << 32
+    elif auth:
+        v |= target
+    else:
+        v |= target | (high8 << 43)
+    return v
+
+
+# DYLD_CHAINED_PTR_64 with DYLD_CHAINED_IMPORT: 4 byte stride, unslid
+# vmaddr targets. The last pointer is not part of the chain.
+chained_fixups('chained_ptr_64', 0x01000007, 3, 2, [
+    ptr64(BASE + 0x800, next=2),
+    ptr64(bind=True, ordinal=0, next=2),
+    ptr64(bind=True, ordinal=1, addend=4, next=2),
+    ptr64(BASE + 0x1010, high8=0x80),
+    0xdead,
+], 1, struct.pack('<II', 1 | (1 << 9), 2 | (1 << 8) | (9 << 9)))
+
+# DYLD_CHAINED_PTR_ARM64E with DYLD_CHAINED_IMPORT_ADDEND64: 8 byte stride,
+# authenticated rebases are image offsets
+chained_fixups('chained_arm64e', 0x0100000c, 2, 1, [
+    arm64e(BASE + 0x800, next=1),
+    arm64e(0x900, next=1, auth=True, diversity=0x1234, addr_div=1, key=2),
+    arm64e(bind=True, ordinal=1, addend=-3, next=1),
+    arm64e(bind=True, auth=True, ordinal=0, diversity=7),
+], 3, struct.pack('<QqQq', 1 | (1 << 32), 0, 0xfffe | (9 << 32), -8))
+
+# Same chain, but with zlib compressed symbol names, which are not decoded
+chained_fixups('chained_zlib_symbols', 0x01000007, 3, 2, [
+    ptr64(BASE + 0x800, next=2),
+    ptr64(bind=True, ordinal=0),
+], 1, struct.pack('<II', 1 | (1 << 9), 2 | (1 << 8) | (9 << 9)), symbols_format=1)
+
+# Export trie of a dylib depending on libother. Values are offsets from the
+# mach header at BASE, except for the absolute symbol.
+exports = MachO(ftype=6)
+exports.linkedit = export_trie({
+    b'_foo': uleb(0) + uleb(0x800),
+    b'_foo_weak': uleb(0x04) + uleb(0x810),
+    b'_tls': uleb(0x01) + uleb(0x900),
+    b'_abs': uleb(0x02) + uleb(0x1234),
+    b'_re': uleb(0x08) + uleb(1) + b'_orig\0',
+    b'_re2': uleb(0x08) + uleb(1) + b'\0',
+    b'_ifunc': uleb(0x10) + uleb(0x820) + uleb(0x830),
+})
+exports.cmds.append(dylib(0xc, b'/usr/lib/libother.dylib'))
+exports.cmds.append(struct.pack(E + 'IIII', 0x80000033, 16, 0x2000, len(exports.linkedit)))
+open('export_trie', 'wb').write(exports.build())
+
+# Export trie whose only child edge leads back to the root
+exports = MachO(ftype=6)
+exports.linkedit = b'\0\x01_\0\x00'
+exports.cmds.append(struct.pack(E + 'IIII', 0x80000033, 16, 0x2000, len(exports.linkedit)))
+open('export_trie_loop', 'wb').write(exports.build())
+
+# Export trie whose root claims a terminal size of 2^64 - 1
+exports = MachO(ftype=6)
+exports.linkedit = uleb(2**64 - 1) + b'\0'
+exports.cmds.append(struct.pack(E + 'IIII', 0x80000033, 16, 0x2000, len(exports.linkedit)))
+open('export_trie_terminal_size', 'wb').write(exports.build())
+
+# Ad-hoc signed executable with SHA-1 and SHA-256 code directories over the
+# first two pages, plus requirements and entitlements blobs
+def code_directory(hash_type, hash_fn, hash_size, pages, requirements, entitlements):
+    ident = b'com.example.fixture\0'
+    team = b'TEAMID1234\0'
+    # Version 0x20400 header, up to and including the exec segment fields
+    header_size = 88
+    ident_off = header_size
+    team_off = ident_off + len(ident)
+    # Special slots 1 (requirements) to 5 (entitlements), stored in reverse
+    special = [b'\0' * hash_size] * 5
+    special[0] = hash_fn(requirements)[:hash_size]
+    special[4] = hash_fn(entitlements)[:hash_size]
+    hash_off = team_off + len(team) + len(special) * hash_size
+    code = b''.join(hash_fn(x)[:hash_size] for x in pages)
+    header = struct.pack('>IIIIIIIIIBBBBIIIIQQQQ', 0xfade0c02, hash_off + len(code), 0x20400, 0x2, hash_off,
+                         ident_off, len(special), len(pages), 0x1000 * len(pages), hash_size, hash_type, 0, 12, 0, 0,
+                         team_off, 0, 0, 0, 0x1000, 1)
+    assert len(header) == header_size
+    return header + ident + team + b''.join(reversed(special)) + code
+
+
+def code_signature(pages):
+    entitlements = (b'<?xml version="1.0"?><plist><dict>'
+                    b'<key>com.apple.security.get-task-allow</key><true/></dict></plist>')
+    entitlements = struct.pack('>II', 0xfade7171, 8 + len(entitlements)) + entitlements
+    requirements = struct.pack('>III', 0xfade0c01, 12, 0)
+    blobs = [(0, code_directory(1, lambda x: hashlib.sha1(x).digest(), 20, pages, requirements, entitlements)),
+             (2, requirements),
+             (5, entitlements),
+             (0x1000, code_directory(2, lambda x: hashlib.sha256(x).digest(), 32, pages, requirements,
+                                     entitlements)),
+             (0x10000, struct.pack('>II', 0xfade0b01, 8))]
+    index = b''
+    body = b''
+    offset = 12 + 8 * len(blobs)
+    for slot, blob in blobs:
+        index += struct.pack('>II', slot, offset + len(body))
+        body += blob
+    return struct.pack('>III', 0xfade0cc0, 12 + len(index) + len(body), len(blobs)) + index + body
+
+
+signed = MachO()
+# The signature covers the load commands, so lay the file out with a
+# placeholder of the same size first
+signed.linkedit = code_signature([b''] * 2)
+signed.cmds.append(struct.pack(E + 'IIII', 0x1d, 16, 0x2000, len(signed.linkedit)))
+image = signed.build()
+signed.linkedit = code_signature([image[:0x1000], image[0x1000:0x2000]])
+image = bytearray(signed.build())
+open('codesign', 'wb').write(image)
+
+# The same file with one byte of the second page changed after signing
+image[0x1001] ^= 1
+open('codesign_modified', 'wb').write(image)
+
+# A signature with an unknown SuperBlob magic
+image[0x1001] ^= 1
+image[0x2003] ^= 1
+open('codesign_corrupt', 'wb').write(image)
+image[0x2003] ^= 1
+
+# The first code directory, after the SuperBlob header and five index
+# entries, with a zero hash size and 2^32 - 1 code slots, and then with a
+# page size of 2^64
+cd = 0x2000 + 12 + 8 * 5
+zero_hash = bytearray(image)
+zero_hash[cd + 28:cd + 32] = b'\xff' * 4
+zero_hash[cd + 36] = 0
+open('codesign_zero_hash_size', 'wb').write(zero_hash)
+image[cd + 39] = 64
+open('codesign_page_size', 'wb').write(image)
+
+
+# Objective-C class with a superclass-less class_t/metaclass pair, a
+# category and a protocol. The metadata is in __objc_const, which follows
+# the four pointer list sections in __DATA.
+def objc(name, meta_method_list):
+    const = BASE + 0x1080
+    blob = bytearray(0x400)
+
+    def put(off, data):
+        blob[off:off + len(data)] = data
+        return const + off
+
+    def q(*v):
+        return struct.pack(E + 'Q' * len(v), *v)
+
+    def i(*v):
+        return struct.pack(E + 'I' * len(v), *v)
+
+    s_name = put(0x300, b'MyClass\0')
+    s_meth = put(0x310, b'doThing:\0')
+    s_types = put(0x320, b'v24@0:8@16\0')
+    s_ivar = put(0x330, b'_count\0')
+    s_ityp = put(0x338, b'q\0')
+    s_proto = put(0x340, b'MyProto\0')
+    s_cat = put(0x350, b'Extra\0')
+    s_cm = put(0x358, b'shared\0')
+    s_cmt = put(0x360, b'@16@0:8\0')
+    s_prop = put(0x370, b'count\0')
+    s_pattr = put(0x378, b'Tq,N,V_count\0')
+    selref = put(0x390, q(s_meth))
+    ivar_offset = put(0x398, i(8))
+    # Relative method list: selector reference, types and imp offsets
+    ml = const + 0x100
+    put(0x100, i(0x80000000 | 12, 1) + struct.pack(E + 'iii', selref - (ml + 8), s_types - (ml + 12),
+                                                     BASE + 0x800 - (ml + 16)))
+    put(0x120, meta_method_list(s_cm, s_cmt))
+    put(0x140, i(32, 1) + q(ivar_offset, s_ivar, s_ityp) + i(3, 8))
+    put(0x168, i(16, 1) + q(s_prop, s_pattr))
+    proto = put(0x180, q(0, s_proto, 0, const + 0x120, 0, 0, 0, 0) + i(80, 0))
+    protocols = put(0x1d0, q(1, proto))
+    # class_ro_t of the class and its metaclass
+    ro = put(0x200, i(0, 8, 16, 0) + q(0, s_name, ml, protocols, const + 0x140, 0, const + 0x168))
+    meta_ro = put(0x248, i(1, 40, 40, 0) + q(0, s_name, const + 0x120, 0, 0, 0, 0))
+    meta = put(0x290, q(0, 0, 0, 0, meta_ro))
+    cls = put(0x2b8, q(meta, 0, 0, 0, ro | 1))
+    cat = put(0x3a0, q(s_cat, cls, ml, 0, protocols, 0))
+    m = MachO()
+    m.extra_sects = [(b'__DATA', b'__objc_classlist', q(cls), 0),
+                     (b'__DATA', b'__objc_catlist', q(cat), 0),
+                     (b'__DATA', b'__objc_protolist', q(proto), 0),
+                     (b'__DATA', b'__objc_selrefs', q(s_meth), 0),
+                     (b'__DATA', b'__objc_const', bytes(blob), 0)]
+    image = m.build()
+    assert m.addrs[b'__objc_const'] == const
+    open(name, 'wb').write(image)
+
+
+# Classic method list with a single method at BASE + 0x810
+objc('objc', lambda name, types: struct.pack(E + 'IIQQQ', 24, 1, name, types, BASE + 0x810))
+
+# Method list claiming 4G zero sized entries
+objc('objc_corrupt', lambda name, types: struct.pack(E + 'II', 0, 0xffffffff))
+
+# A class list entry at the very top of the address space
+wrapped = MachO()
+wrapped.extra_sects = [(b'__DATA', b'__objc_classlist', struct.pack(E + 'Q', 2**64 - 8), 0)]
+open('objc_wrapped_pointer', 'wb').write(wrapped.build())
+
+
+# Swift module MyMod with a struct Point { var x: Int; let y: Point }, a
+# protocol Shape and Point's conformance to it. The descriptors are in
+# __TEXT,__const, after the three __swift5_* lists.
+def swift(name, extension_cycle=False, field_count=2):
+    const = BASE + 0x870
+    blob = bytearray(0x200)
+
+    def put(off, data):
+        blob[off:off + len(data)] = data
+        return const + off
+
+    def rel(frm, to):
+        return struct.pack(E + 'i', to - frm)
+
+    s_mod = put(0x180, b'MyMod\0')
+    s_point = put(0x188, b'Point\0')
+    s_x = put(0x190, b'x\0')
+    s_y = put(0x194, b'y\0')
+    s_int = put(0x198, b'Si\0')
+    s_shape = put(0x1a0, b'Shape\0')
+    s_inner = put(0x1a8, b'Inner\0')
+    module = put(0, struct.pack(E + 'Ii', 0, 0) + rel(const + 8, s_mod))
+    # Unique struct: flags, parent, name, access function, fields, field
+    # count and offset
+    point = const + 0x10
+    put(0x10, struct.pack(E + 'I', 17 | 0x40) + rel(point + 4, module) + rel(point + 8, s_point) +
+        struct.pack(E + 'i', 0) + rel(point + 16, const + 0x40) + struct.pack(E + 'II', 2, 2))
+    # Symbolic reference to Point
+    point_ref = put(0x100, b'\x01' + rel(const + 0x101, point) + b'\0')
+    # Field descriptor: type name, superclass, kind, record size, count
+    fields = const + 0x40
+    put(0x40, rel(fields, point_ref) + struct.pack(E + 'iHHI', 0, 0, 12, field_count))
+    put(0x50, struct.pack(E + 'I', 2) + rel(fields + 20, s_int) + rel(fields + 24, s_x))
+    put(0x5c, struct.pack(E + 'I', 0) + rel(fields + 32, point_ref) + rel(fields + 36, s_y))
+    # Protocol: flags, parent, name, requirements in signature, requirements
+    shape = const + 0x80
+    put(0x80, struct.pack(E + 'I', 3) + rel(shape + 4, module) + rel(shape + 8, s_shape) +
+        struct.pack(E + 'IIi', 0, 1, 0))
+    conformance = const + 0xa0
+    put(0xa0, rel(conformance, shape) + rel(conformance + 4, point) + struct.pack(E + 'iI', 0, 0))
+    types = rel(BASE + 0x840, point)
+    if extension_cycle:
+        # Struct Inner nested in an extension whose extended type refers
+        # back to the extension itself
+        extension = const + 0xc0
+        extended = const + 0x110
+        put(0x110, b'\x01' + rel(extended + 1, extension) + b'\0')
+        put(0xc0, struct.pack(E + 'I', 1) + rel(extension + 4, module) + rel(extension + 8, extended))
+        inner = const + 0xe0
+        put(0xe0, struct.pack(E + 'I', 17) + rel(inner + 4, extension) + rel(inner + 8, s_inner) +
+            struct.pack(E + 'iiII', 0, 0, 0, 0))
+        types += rel(BASE + 0x844, inner)
+    m = MachO()
+    m.extra_sects = [(b'__TEXT', b'__swift5_types', types, 0),
+                     (b'__TEXT', b'__swift5_protos', rel(BASE + 0x850, shape), 0),
+                     (b'__TEXT', b'__swift5_proto', rel(BASE + 0x860, conformance), 0),
+                     (b'__TEXT', b'__const', bytes(blob), 0)]
+    image = m.build()
+    assert m.addrs[b'__const'] == const
+    open(name, 'wb').write(image)
+
+
+swift('swift')
+swift('swift_extension_cycle', extension_cycle=True)
+swift('swift_corrupt', field_count=0xffffffff)
+
+# Indirect protocol reference through a __data slot at the very top of the
+# address space
+swift_wrapped = MachO()
+swift_wrapped.data = struct.pack(E + 'Q', 2**64 - 8) + b'\0' * 0x38
+swift_wrapped.extra_sects = [(b'__TEXT', b'__swift5_protos', struct.pack(E + 'i', 0x1000 - 0x840 + 1), 0)]
+image = swift_wrapped.build()
+assert swift_wrapped.addrs[b'__swift5_protos'] == BASE + 0x840
+open('swift_wrapped_pointer', 'wb').write(image)
+
+
+class PE:
+    """PE32+ DLL at 0x180000000 with .text at RVA 0x1000, .rdata at 0x2000
+    and .data at 0x3000, each 0x200 bytes in the file from offset 0x400"""
+
+    def __init__(self):
+        self.base = 0x180000000
+        self.text = b'\xc3' * 0x40
+        self.rdata = bytearray(0x400)
+        self.data = bytearray(0x200)
+        self.dirs = [(0, 0)] * 16
+        # PointerToSymbolTable and NumberOfSymbols
+        self.symtab = (0, 0)
+        # PointerToRelocations and NumberOfRelocations by section name
+        self.relocs = {}
+
+    def put(self, off, data):
+        """Store `data` at `off` in .rdata and return its RVA"""
+        self.rdata[off:off + len(data)] = data
+        return 0x2000 + off
+
+    def build(self):
+        sects = [(b'.text', 0x1000, bytes(self.text), 0x60000020),
+                 (b'.rdata', 0x2000, bytes(self.rdata), 0x40000040),
+                 (b'.data', 0x3000, bytes(self.data), 0xc0000040)]
+        hdrs = b''
+        body = b''
+        for name, rva, data, flags in sects:
+            size = (len(data) + 0x1ff) // 0x200 * 0x200
+            relocs = self.relocs.get(name, (0, 0))
+            hdrs += struct.pack('<8sIIIIIIHHI', name, len(data), rva, size, 0x400 + len(body), relocs[0], 0,
+                                relocs[1], 0, flags)
+            body += data + b'\0' * (size - len(data))
+        coff = struct.pack('<HHIIIHH', 0x8664, len(sects), 0, self.symtab[0], self.symtab[1], 0xf0, 0x2022)
+        opt = struct.pack('<HBBIIIIIQIIHHHHHHIIIIHHQQQQII', 0x20b, 14, 0, 0x200, 0x400, 0, 0x1000, 0x1000,
+                          self.base, 0x1000, 0x200, 6, 0, 0, 0, 6, 0, 0, 0x4000, 0x400, 0, 2, 0x160,
+                          0x100000, 0x1000, 0x100000, 0x1000, 0, 16)
+        opt += b''.join(struct.pack('<II', *x) for x in self.dirs)
+        out = b'MZ' + b'\0' * 0x3a + struct.pack('<I', 0x40) + b'PE\0\0' + coff + opt + hdrs
+        return out + b'\0' * (0x400 - len(out)) + body
+
+
+# test.dll exporting alpha and its alias alpha2, an ordinal-only function and
+# a forwarder, and importing from KERNEL32.dll and, delay loaded, USER32.dll.
+# An RVA outside all sections can replace the export ordinal table or the
+# hint/name of Sleep, and the COFF symbol table can point past the end of the
+# file.
+def pe_dll(name, bad_ordinals=False, bad_hint=False, bad_symtab=False):
+    p = PE()
+    if bad_symtab:
+        p.symtab = (0x100000, 1000)
+    dll = p.put(0x200, b'test.dll\0')
+    alpha = p.put(0x210, b'alpha\0')
+    alpha2 = p.put(0x218, b'alpha2\0')
+    fwd = p.put(0x220, b'fwd\0')
+    target = p.put(0x1e0, b'KERNEL32.Sleep\0')
+    funcs = p.put(0x140, struct.pack('<III', 0x1000, 0x1010, target))
+    names = p.put(0x150, struct.pack('<III', alpha, alpha2, fwd))
+    ordinals = 0x9000 if bad_ordinals else p.put(0x160, struct.pack('<HHH', 0, 0, 2))
+    p.put(0x100, struct.pack('<IIHHIIIIIII', 0, 0, 0, 0, dll, 1, 3, 3, funcs, names, ordinals))
+
+    kernel32 = p.put(0x300, b'KERNEL32.dll\0')
+    user32 = p.put(0x310, b'USER32.dll\0')
+    sleep = 0x9000 if bad_hint else p.put(0x320, b'\x05\x00Sleep\0')
+    message_box = p.put(0x330, b'\x00\x00MessageBoxA\0')
+    thunks = struct.pack('<QQQ', sleep, (1 << 63) | 17, 0)
+    lookup = p.put(0x240, thunks)
+    iat = p.put(0x260, thunks)
+    p.put(0x280, struct.pack('<IIIII', lookup, 0, 0, kernel32, iat) + b'\0' * 20)
+    delay_lookup = p.put(0x2c0, struct.pack('<QQ', message_box, 0))
+    p.put(0x380, struct.pack('<8I', 1, user32, 0, 0x3000, delay_lookup, 0, 0, 0) + b'\0' * 32)
+    p.dirs[0] = (0x2100, 0x100)
+    p.dirs[1] = (0x2280, 40)
+    p.dirs[13] = (0x2380, 64)
+    open(name, 'wb').write(p.build())
+
+
+pe_dll('pe_dll')
+pe_dll('pe_bad_export_ordinals', bad_ordinals=True)
+pe_dll('pe_bad_import_hint', bad_hint=True)
+pe_dll('pe_bad_symtab', bad_symtab=True)
+
+
+# Base relocations: a block for page 0x3000 with two DIR64 entries and an
+# ABSOLUTE padding entry, and a block for page 0x2000 with a HIGHLOW entry.
+# A block size running past 4GB can replace the second block.
+def pe_base_relocs(name, bad_size=False):
+    p = PE()
+    blocks = struct.pack('<IIHHHH', 0x3000, 16, (10 << 12) | 0x10, (10 << 12) | 0x18, 0, 0)
+    blocks += struct.pack('<IIHH', 0x2000, 0xfffffff8 if bad_size else 12, (3 << 12) | 0x8, 0)
+    p.dirs[5] = (p.put(0x100, blocks), len(blocks))
+    open(name, 'wb').write(p.build())
+
+
+pe_base_relocs('pe_base_relocs')
+pe_base_relocs('pe_bad_base_relocs', bad_size=True)
+
+
+# x86-64 shared object importing puts from libc.so.6 and exporting the int
+# `answer` at the start of .data. The first PT_LOAD maps the headers,
+# dynamic symbols and .text read/execute at 0; the second maps .data and
+# .dynamic from file offset 0x1000 read/write at 0x2000, followed by .bss.
+def elf_dyn(name):
+    dynstr = b'\0libc.so.6\0puts\0answer\0'
+    dynsym = bytes(24)
+    dynsym += struct.pack('<IBBHQQ', 11, 0x12, 0, 0, 0, 0)
+    dynsym += struct.pack('<IBBHQQ', 16, 0x11, 0, 2, 0x2000, 4)
+    # R_X86_64_GLOB_DAT of puts and an R_X86_64_RELATIVE, both in .data
+    rela = struct.pack('<QQq', 0x2008, (1 << 32) | 6, 0) + struct.pack('<QQq', 0x2010, 8, 0x200)
+    text = b'\xc3' * 0x10
+    data = struct.pack('<I', 42) + b'\0' * 0x1c
+    dynamic = struct.pack('<QQQQ', 1, 1, 0, 0)
+    shstrtab = b'\0.text\0.data\0.bss\0.dynsym\0.dynstr\0.dynamic\0.rela.dyn\0.shstrtab\0'
+
+    out = bytearray(0x1080)
+    for off, blob in [(0x100, dynsym), (0x150, dynstr), (0x180, rela), (0x200, text), (0x1000, data),
+                      (0x1020, dynamic), (0x1040, shstrtab)]:
+        out[off:off + len(blob)] = blob
+    out[0:64] = b'\x7fELF\x02\x01\x01' + b'\0' * 9 + struct.pack('<HHIQQQIHHHHHH', 3, 0x3e, 1, 0x200, 64, 0x1080,
+                                                                  0, 64, 56, 3, 64, 9, 8)
+    out[64:64 + 3 * 56] = (struct.pack('<IIQQQQQQ', 1, 5, 0, 0, 0, 0x210, 0x210, 0x1000) +
+                           struct.pack('<IIQQQQQQ', 1, 6, 0x1000, 0x2000, 0x2000, 0x40, 0x80, 0x1000) +
+                           struct.pack('<IIQQQQQQ', 2, 6, 0x1020, 0x2020, 0x2020, 0x20, 0x20, 8))
+
+    def shdr(name, shtype, flags, addr, off, size, link=0, info=0, align=1, entsize=0):
+        return struct.pack('<IIQQQQIIQQ', shstrtab.index(name + b'\0'), shtype, flags, addr, off, size, link, info,
+                           align, entsize)
+
+    out += bytes(64)
+    out += shdr(b'.text', 1, 6, 0x200, 0x200, len(text), align=16)
+    out += shdr(b'.data', 1, 3, 0x2000, 0x1000, len(data), align=8)
+    out += shdr(b'.bss', 8, 3, 0x2040, 0x1040, 0x40, align=8)
+    out += shdr(b'.dynsym', 11, 2, 0x100, 0x100, len(dynsym), link=5, info=1, align=8, entsize=24)
+    out += shdr(b'.dynstr', 3, 2, 0x150, 0x150, len(dynstr))
+    out += shdr(b'.dynamic', 6, 3, 0x2020, 0x1020, len(dynamic), link=5, align=8, entsize=16)
+    out += shdr(b'.rela.dyn', 4, 2, 0x180, 0x180, len(rela), link=4, align=8, entsize=24)
+    out += shdr(b'.shstrtab', 3, 0, 0, 0x1040, len(shstrtab))
+    open(name, 'wb').write(out)
+
+
+elf_dyn('elf_dyn')
+
+
+# Executable with a local _helper and a global _main in __text, the
+# undefined _printf and an LC_MAIN entry point at _main
+symbols = MachO()
+strings = b'\0_helper\0_main\0_printf\0'
+symbols.linkedit = (struct.pack(E + 'IBBHQ', 1, 0x0e, 1, 0, BASE + 0x810) +
+                    struct.pack(E + 'IBBHQ', 9, 0x0f, 1, 0, BASE + 0x800) +
+                    struct.pack(E + 'IBBHQ', 15, 0x01, 0, 0x100, 0) + strings)
+symbols.cmds.append(dylib(0xc, b'/usr/lib/libSystem.B.dylib'))
+symbols.cmds.append(struct.pack(E + 'IIIIII', 0x2, 24, 0x2000, 3, 0x2030, len(strings)))
+symbols.cmds.append(struct.pack(E + 'IIQQ', 0x80000028, 24, 0x800, 0))
+open('mach_symbols', 'wb').write(symbols.build())
+
+
+# LC_SYMTAB whose nlist entries start past the end of the file
+bad_symtab = MachO()
+bad_symtab.linkedit = b'\0'
+bad_symtab.cmds.append(struct.pack(E + 'IIIIII', 0x2, 24, 0x100000, 1000, 0x2000, 1))
+open('mach_bad_symtab', 'wb').write(bad_symtab.build())
+
+
+# LC_UNIXTHREAD with an x86_THREAD_STATE64 flavor but no register state
+bad_thread = MachO()
+bad_thread.cmds.append(struct.pack(E + 'IIII', 0x5, 16, 4, 42))
+open('mach_bad_thread', 'wb').write(bad_thread.build())
+
+
+# LC_DYLD_INFO_ONLY opcode streams against __DATA (segment 2): two rebases
+# at +0x10, _printf from libSystem with addend 8 at +0x20, a weak bind of
+# __ZdlPv at +0x28, and lazy binds of the weak import _objc_msgSend from
+# libobjc at +0x30 and a flat lookup _dlsym at +0x38
+dyld_info = MachO()
+rebase = b'\x11\x22' + uleb(0x10) + b'\x52\x00'
+bind = b'\x11\x40_printf\0\x51\x72' + uleb(0x20) + b'\x60\x08\x90\x00'
+weak_bind = b'\x40__ZdlPv\0\x51\x72' + uleb(0x28) + b'\x90\x00'
+lazy_bind = (b'\x72' + uleb(0x30) + b'\x12\x41_objc_msgSend\0\x90\x00' +
+             b'\x72' + uleb(0x38) + b'\x3e\x40_dlsym\0\x90\x00')
+streams = []
+for stream in [rebase, bind, weak_bind, lazy_bind]:
+    streams += [0x2000 + len(dyld_info.linkedit), len(stream)]
+    dyld_info.linkedit += align(stream, 8)
+dyld_info.cmds.append(dylib(0xc, b'/usr/lib/libSystem.B.dylib'))
+dyld_info.cmds.append(dylib(0xc, b'/usr/lib/libobjc.A.dylib'))
+dyld_info.cmds.append(struct.pack(E + 'II10I', 0x80000022, 48, *(streams + [0, 0])))
+open('mach_dyld_info', 'wb').write(dyld_info.build())
+
+
+# Functions at __text + 0, 0x10 and 0x30, with an 8 byte jump table at
+# __text + 0x20 and 4 bytes of data at __text + 0x38
+starts = MachO()
+function_starts = align(uleb(0x800) + uleb(0x10) + uleb(0x20) + b'\0', 8)
+dice = struct.pack(E + 'IHH', 0x820, 8, 4) + struct.pack(E + 'IHH', 0x838, 4, 1)
+starts.linkedit = function_starts + dice
+starts.cmds.append(struct.pack(E + 'IIII', 0x26, 16, 0x2000, len(function_starts)))
+starts.cmds.append(struct.pack(E + 'IIII', 0x29, 16, 0x2000 + len(function_starts), len(dice)))
+open('mach_function_starts', 'wb').write(starts.build())
+
+
+# LC_DYSYMTAB over the defined _main and undefined _printf and _exit, with
+# __TEXT,__stubs (6 byte stubs for _printf and _exit) and __DATA,__got
+# (_exit and a local pointer) named through the indirect symbol table
+dysymtab = MachO()
+dysymtab.extra_sects = [(b'__TEXT', b'__stubs', b'\xff\x25\0\0\0\0' * 2, 0x80000408),
+                        (b'__DATA', b'__got', b'\0' * 16, 6)]
+dysymtab.reserved = {b'__stubs': (0, 6), b'__got': (2, 0)}
+strings = b'\0_main\0_printf\0_exit\0'
+indirect = struct.pack(E + '4I', 1, 2, 2, 0x80000000)
+dysymtab.linkedit = (struct.pack(E + 'IBBHQ', 1, 0x0f, 1, 0, BASE + 0x800) +
+                     struct.pack(E + 'IBBHQ', 7, 0x01, 0, 0x100, 0) +
+                     struct.pack(E + 'IBBHQ', 15, 0x01, 0, 0x100, 0) + indirect + strings)
+dysymtab.cmds.append(dylib(0xc, b'/usr/lib/libSystem.B.dylib'))
+dysymtab.cmds.append(struct.pack(E + 'IIIIII', 0x2, 24, 0x2000, 3, 0x2000 + 48 + 16, len(strings)))
+dysymtab.cmds.append(struct.pack(E + 'II18I', 0xb, 80, 0, 0, 0, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0x2000 + 48, 4,
+                                 0, 0, 0, 0))
+open('mach_dysymtab', 'wb').write(dysymtab.build())
+
+
+# ARM64 object file whose __text has a PAGE21 to _bar + 16 and a PAGEOFF12
+# to _bar - 8 (each with an ADDEND entry), a branch to _foo and a non-extern
+# pointer into section 1
+def arm64_reloc(address, symbolnum, pcrel, length, extern, r_type):
+    return struct.pack(E + 'II', address, symbolnum | pcrel << 24 | length << 25 | extern << 27 | r_type << 28)
+
+
+obj_relocs = MachO(cpu=0x0100000c, sub=0, ftype=1)
+strings = b'\0_foo\0_bar\0'
+relocs = (arm64_reloc(4, 16, 0, 2, 0, 10) + arm64_reloc(4, 1, 1, 2, 1, 3) +
+          arm64_reloc(8, 0xfffff8, 0, 2, 0, 10) + arm64_reloc(8, 1, 0, 2, 1, 4) +
+          arm64_reloc(0xc, 0, 1, 2, 1, 2) + arm64_reloc(0x10, 1, 0, 3, 0, 0))
+obj_relocs.linkedit = (relocs + struct.pack(E + 'IBBHQ', 1, 0x01, 0, 0, 0) +
+                       struct.pack(E + 'IBBHQ', 6, 0x01, 0, 0, 0) + strings)
+obj_relocs.relocs = {b'__text': (0x2000, 6)}
+obj_relocs.cmds.append(struct.pack(E + 'IIIIII', 0x2, 24, 0x2000 + len(relocs), 2, 0x2000 + len(relocs) + 32,
+                                   len(strings)))
+open('mach_object_relocs', 'wb').write(obj_relocs.build())
+
+
+# LC_UUID, a Mac Catalyst 14.0 LC_BUILD_VERSION built with ld 1015.7 and a
+# legacy macOS 10.15 LC_VERSION_MIN_MACOSX
+versions = MachO()
+versions.cmds.append(struct.pack(E + 'II', 0x1b, 24) + bytes(range(16)))
+versions.cmds.append(struct.pack(E + 'IIIIIIII', 0x32, 32, 6, 0x000e0000, 0x00110500, 1, 3, 0x03f70700))
+versions.cmds.append(struct.pack(E + 'IIII', 0x24, 16, 0x000a0f00, 0x000b0000))
+open('mach_versions', 'wb').write(versions.build())
+
+
+# C strings in __TEXT and an initializer pointer in __DATA
+sect_types = MachO()
+sect_types.extra_sects = [(b'__TEXT', b'__cstring', b'hello\0', 2),
+                          (b'__DATA', b'__mod_init_func', struct.pack(E + 'Q', BASE + 0x800), 9)]
+open('mach_section_types', 'wb').write(sect_types.build())
+
+
+# Header claiming a single load command of almost 4GB
+huge_cmds = bytearray(MachO().build())
+struct.pack_into(E + 'II', huge_cmds, 16, 1, 0xfffffff0)
+struct.pack_into(E + 'II', huge_cmds, 32, 0x2, 0xfffffff0)
+open('mach_huge_cmds', 'wb').write(huge_cmds)
+
+
+def segment32(name, vm, off, size, prot, sects):
+    body = b''.join(struct.pack(E + '16s16sIIIIIIIII', sect, name, addr, len(data), 0 if flags == 1 else addr - vm + off,
+                                4, 0, 0, flags, 0, 0) for sect, addr, data, flags in sects)
+    return struct.pack(E + 'II16sIIIIiiII', 0x1, 56 + 68 * len(sects), name, vm, size, off, size if prot else 0,
+                       prot, prot, len(sects), 0) + body
+
+
+def mach32(cpu, sub, text, data, cmds=[]):
+    """32-bit little endian executable: __PAGEZERO, __TEXT (vm 0x1000) with
+    __text at file offset 0x800, and __DATA (vm 0x2000, file 0x1000) with
+    __data and a zero-fill __bss"""
+    segs = [segment32(b'__PAGEZERO', 0, 0, 0x1000, 0, []),
+            segment32(b'__TEXT', 0x1000, 0, 0x1000, 5, [(b'__text', 0x1800, text, 0x80000400)]),
+            segment32(b'__DATA', 0x2000, 0x1000, 0x1000, 3, [(b'__data', 0x2000, data, 0),
+                                                               (b'__bss', 0x2010, b'\0' * 0x20, 1)])] + cmds
+    body = b''.join(segs)
+    out = bytearray(struct.pack(E + 'IiIIIII', 0xfeedface, cpu, sub, 2, len(segs), len(body), 0x85) + body)
+    out += b'\0' * (0x2000 - len(out))
+    out[0x800:0x800 + len(text)] = text
+    out[0x1000:0x1000 + len(data)] = data
+    return bytes(out)
+
+
+# i386 executable with an LC_UNIXTHREAD entry point at the start of __text
+i386_thread = struct.pack(E + 'IIII', 0x5, 80, 1, 16) + struct.pack(E + '16I', *([0] * 10 + [0x1800] + [0] * 5))
+open('mach_i386', 'wb').write(mach32(7, 3, b'\x90' * 15 + b'\xc3', struct.pack(E + 'II', 42, 0x1800), [i386_thread]))
+
+
+def fat(name, slices):
+    """Universal binary of (cputype, cpusubtype, image) slices, each 16K
+    aligned"""
+    hdr = struct.pack('>II', 0xcafebabe, len(slices))
+    out = b''
+    for cpu, sub, image in slices:
+        off = 0x4000 * (len(out) // 0x4000 + 1)
+        hdr += struct.pack('>iIIII', cpu, sub, off, len(image), 14)
+        out += b'\0' * (off - len(out)) + image
+    open(name, 'wb').write(hdr + out[len(hdr):])
+
+
+# Universal binary with an x86_64 slice and an arm64 slice whose header has
+# a bad magic number
+fat('fat_corrupt_slice', [(0x01000007, 3, MachO().build()),
+                          (0x0100000c, 0, b'\0' * 4 + MachO(cpu=0x0100000c, sub=0).build()[4:])])
+
+# armv7k, armv7em, arm64_32 and arm64e (pointer authentication ABI version 1)
+# slices
+fat('fat_arm', [(12, 12, mach32(12, 12, b'\0' * 4, b'')),
+                (12, 16, mach32(12, 16, b'\0' * 4, b'')),
+                (0x0200000c, 1, mach32(0x0200000c, 1, b'\0' * 4, b'')),
+                (0x0100000c, 0x81000002, MachO(cpu=0x0100000c, sub=0x81000002).build())])
+
+
+# __const in both __TEXT and __DATA
+consts = MachO()
+consts.extra_sects = [(b'__TEXT', b'__const', b'\x01' * 8, 0), (b'__DATA', b'__const', b'\x02' * 8, 0)]
+open('mach_duplicate_sections', 'wb').write(consts.build())
+
+
+# COFF relocations: two IMAGE_REL_AMD64_ADDR64 in .text, stored in .rdata
+# (file offset 0x600), and a .data table past the end of the file
+coff_relocs = PE()
+coff_relocs.put(0x380, struct.pack('<IIH', 8, 0, 1) + struct.pack('<IIH', 0x10, 0, 1))
+coff_relocs.relocs = {b'.text': (0x600 + 0x380, 2), b'.data': (0x100000, 3)}
+open('pe_coff_relocs', 'wb').write(coff_relocs.build())
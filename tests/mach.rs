@@ -0,0 +1,433 @@
+extern crate execfmt;
+
+use std::fs;
+use execfmt::{ARMMode, ARMType, Arch, Endianness, Object, Width};
+use execfmt::mach::FatFile;
+use execfmt::mach::file::File;
+use execfmt::mach::types;
+
+/// Open a fixture built by tests/fixtures/mkfixtures.py
+fn open(name: &str) -> fs::File {
+    fs::File::open(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn parse(name: &str) -> File {
+    File::parse(&mut open(name)).unwrap()
+}
+
+#[test]
+fn segments_32() {
+    let file = parse("mach_i386");
+    assert!(file.header().class == types::MACH_CLASS_32);
+    let segments: Vec<_> = file.segments().iter().map(|x| (x.seg_name.as_str(), x.vm_addr, x.vm_size, x.file_off))
+        .collect();
+    assert_eq!(segments, vec![("__PAGEZERO", 0, 0x1000, 0), ("__TEXT", 0x1000, 0x1000, 0),
+                              ("__DATA", 0x2000, 0x1000, 0x1000)]);
+
+    let text = file.section_header("__TEXT", "__text").unwrap();
+    assert_eq!((text.addr, text.size, text.offset, text.align), (0x1800, 0x10, 0x800, 4));
+    let sections: Vec<_> = file.sections().iter().map(|x| (x.name(), x.addr(), x.size())).collect();
+    assert_eq!(sections, vec![("__text", 0x1800, 0x10), ("__data", 0x2000, 8), ("__bss", 0x2010, 0x20)]);
+    assert_eq!(file.sections()[0].data()[15], 0xc3);
+    assert_eq!(file.sections()[1].data(), &[42, 0, 0, 0, 0, 0x18, 0, 0][..]);
+    assert!(file.sections()[2].data().is_empty());
+    assert_eq!(file.entrypoint(), Some(0x1800));
+}
+
+#[test]
+fn segment_layout() {
+    let file = parse("mach_section_types");
+    let segments: Vec<_> = file.segments().iter()
+        .map(|x| (x.seg_name.as_str(), x.file_off, x.filesize, x.maxprot, x.initprot, x.sections.len()))
+        .collect();
+    assert_eq!(segments, vec![("__PAGEZERO", 0, 0, 0, 0, 0), ("__TEXT", 0, 0x1000, 5, 5, 2),
+                              ("__DATA", 0x1000, 0x1000, 3, 3, 2), ("__LINKEDIT", 0x2000, 1, 1, 1, 0)]);
+    assert!(file.segments()[1].is_executable() && !file.segments()[1].is_writable());
+    assert!(file.segments()[2].is_writable() && !file.segments()[2].is_executable());
+
+    let cstring = file.section_header("__TEXT", "__cstring").unwrap();
+    assert_eq!((cstring.addr, cstring.size, cstring.offset), (0x100000840, 6, 0x840));
+    assert_eq!(cstring.sect_type(), types::S_CSTRING_LITERALS);
+    let init = file.section_header("__DATA", "__mod_init_func").unwrap();
+    assert_eq!((init.addr, init.offset, init.align, init.nreloc), (0x100001040, 0x1040, 4, 0));
+    assert_eq!(init.sect_type(), types::S_MOD_INIT_FUNC_POINTERS);
+    assert!(file.section_header("__DATA", "__cstring").is_none());
+}
+
+#[test]
+fn uuid_and_versions() {
+    let file = parse("mach_versions");
+    assert_eq!(file.uuid().unwrap(), &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+
+    let builds = file.build_versions();
+    assert_eq!(builds.len(), 1);
+    assert!(builds[0].platform == types::PLATFORM_MACCATALYST && !builds[0].platform.is_simulator());
+    assert_eq!(builds[0].minos.to_string(), "14.0.0");
+    assert_eq!(builds[0].sdk.to_string(), "17.5.0");
+    assert_eq!(builds[0].tools.len(), 1);
+    assert!(builds[0].tools[0].tool == types::TOOL_LD);
+    assert_eq!(builds[0].tools[0].version.to_string(), "1015.7.0");
+
+    let min = file.version_min().unwrap();
+    assert!(min.platform() == types::PLATFORM_MACOS);
+    assert_eq!((min.version.major(), min.version.minor()), (10, 15));
+    assert_eq!(min.sdk.to_string(), "11.0.0");
+}
+
+#[test]
+fn arm_arch() {
+    let mut f = open("fat_arm");
+    let fat = FatFile::parse(&mut f).unwrap();
+    let arm = |width, mode, kind| Arch::ARM(width, Endianness::Little, mode, kind);
+    let archs: Vec<_> = fat.archs().iter().map(|x| x.arch()).collect();
+    assert_eq!(archs, vec![arm(Width::W32, ARMMode::ARM, ARMType::ARM),
+                           arm(Width::W32, ARMMode::Thumb, ARMType::MClass),
+                           arm(Width::W32, ARMMode::ARM, ARMType::V8),
+                           arm(Width::W64, ARMMode::ARM, ARMType::V8)]);
+
+    let slices: Vec<_> = fat.slices(&mut f).into_iter().map(|x| x.unwrap()).collect();
+    for (slice, arch) in slices.iter().zip(archs) {
+        assert_eq!(slice.arch(), arch);
+    }
+    assert_eq!(slices[0].header().subtype(), types::CPU_SUBTYPE_ARM_V7K);
+    assert_eq!(slices[1].header().subtype(), types::CPU_SUBTYPE_ARM_V7EM);
+    assert!(slices[2].header().cpu_type == types::PM_ARM64_32);
+    assert_eq!(slices[3].header().subtype(), types::CPU_SUBTYPE_ARM64E);
+    assert_eq!(slices[3].header().ptrauth_version(), Some(1));
+    assert_eq!(fat.archs()[3].ptrauth_version(), Some(1));
+    assert_eq!(slices[2].header().ptrauth_version(), None);
+}
+
+#[test]
+fn dyld_info_opcodes() {
+    let file = parse("mach_dyld_info");
+    assert!(file.decode_errors().is_empty());
+    let rebases: Vec<_> = file.rebases().iter().map(|x| (x.seg_index, x.seg_offset, x.address, x.rebase_type))
+        .collect();
+    assert_eq!(rebases, vec![(2, 0x10, 0x100001010, types::REBASE_TYPE_POINTER),
+                             (2, 0x18, 0x100001018, types::REBASE_TYPE_POINTER)]);
+
+    let binds: Vec<_> = file.binds().iter()
+        .map(|x| (x.kind, x.address, x.symbol_name.as_str(), x.library_ordinal, x.library.as_deref(), x.addend))
+        .collect();
+    assert_eq!(binds, vec![
+        (types::BindKind::Regular, 0x100001020, "_printf", 1, Some("/usr/lib/libSystem.B.dylib"), 8),
+        (types::BindKind::Weak, 0x100001028, "__ZdlPv", 0, None, 0),
+        (types::BindKind::Lazy, 0x100001030, "_objc_msgSend", 2, Some("/usr/lib/libobjc.A.dylib"), 0),
+        (types::BindKind::Lazy, 0x100001038, "_dlsym", types::BIND_SPECIAL_DYLIB_FLAT_LOOKUP, None, 0),
+    ]);
+    assert!(file.binds()[2].is_weak_import());
+    assert!(!file.binds()[0].is_weak_import());
+}
+
+#[test]
+fn function_starts_and_data_in_code() {
+    let file = parse("mach_function_starts");
+    assert_eq!(file.function_starts(), &[0x100000800, 0x100000810, 0x100000830][..]);
+
+    let dice: Vec<_> = file.data_in_code().iter().map(|x| (x.offset, x.length, x.kind, x.address)).collect();
+    assert_eq!(dice, vec![(0x820, 8, types::DICE_KIND_JUMP_TABLE32, 0x100000820),
+                          (0x838, 4, types::DICE_KIND_DATA, 0x100000838)]);
+    assert!(file.data_in_code()[0].is_jump_table());
+    assert!(!file.data_in_code()[1].is_jump_table());
+}
+
+#[test]
+fn dysymtab_and_indirect_symbols() {
+    let file = parse("mach_dysymtab");
+    assert!(file.decode_errors().is_empty());
+    let dysymtab = file.dysymtab().unwrap();
+    assert_eq!((dysymtab.cmd.ilocalsym, dysymtab.cmd.nlocalsym), (0, 0));
+    assert_eq!((dysymtab.cmd.iextdefsym, dysymtab.cmd.nextdefsym), (0, 1));
+    assert_eq!((dysymtab.cmd.iundefsym, dysymtab.cmd.nundefsym), (1, 2));
+    assert_eq!(dysymtab.indirect_symbols, vec![1, 2, 2, types::INDIRECT_SYMBOL_LOCAL]);
+    assert!(dysymtab.toc.is_empty() && dysymtab.modules.is_empty());
+
+    let indirect: Vec<_> = file.indirect_symbols().iter()
+        .map(|x| (x.address, x.sect_name.as_str(), x.index, x.symbol.as_deref()))
+        .collect();
+    assert_eq!(indirect, vec![(0x100000840, "__stubs", 1, Some("_printf")),
+                              (0x100000846, "__stubs", 2, Some("_exit")),
+                              (0x100001040, "__got", 2, Some("_exit")),
+                              (0x100001048, "__got", types::INDIRECT_SYMBOL_LOCAL, None)]);
+    assert_eq!(file.indirect_symbol_name(0x100000846), Some("_exit"));
+    assert_eq!(file.indirect_symbol_name(0x100001048), None);
+}
+
+#[test]
+fn object_relocations() {
+    let file = parse("mach_object_relocs");
+    assert_eq!(file.header().file_type, 1);
+    let arm64 = types::RelocationKind::Arm64;
+    let relocs: Vec<_> = file.relocations("__TEXT", "__text").iter()
+        .map(|x| (x.address, x.kind, x.pcrel, x.length, x.is_extern, x.symbol.as_deref(), x.addend))
+        .collect();
+    assert_eq!(relocs, vec![(4, arm64(types::Arm64Reloc::Page21), true, 2, true, Some("_bar"), 16),
+                            (8, arm64(types::Arm64Reloc::PageOff12), false, 2, true, Some("_bar"), -8),
+                            (0xc, arm64(types::Arm64Reloc::Branch26), true, 2, true, Some("_foo"), 0),
+                            (0x10, arm64(types::Arm64Reloc::Unsigned), false, 3, false, None, 0)]);
+    assert_eq!(file.relocations("__TEXT", "__text")[3].symbolnum, 1);
+    assert!(file.relocations("__DATA", "__data").is_empty());
+}
+
+#[test]
+fn chained_fixups_ptr_64() {
+    let file = parse("chained_ptr_64");
+    let fixups = file.chained_fixups().unwrap();
+    assert_eq!(fixups.starts.len(), 1);
+    assert_eq!(fixups.starts[0].seg_index, 2);
+
+    let rebases: Vec<(u64, u64)> = fixups.rebases.iter().map(|x| (x.address, x.target)).collect();
+    assert_eq!(rebases, vec![(0x100001000, 0x100000800), (0x100001018, 0x8000000100001010)]);
+
+    assert_eq!(fixups.binds.len(), 2);
+    assert_eq!(fixups.binds[0].address, 0x100001008);
+    assert_eq!(fixups.binds[0].symbol_name, "_printf");
+    assert_eq!(fixups.binds[0].library.as_ref().unwrap(), "/usr/lib/libSystem.B.dylib");
+    assert!(!fixups.binds[0].weak_import);
+    assert_eq!(fixups.binds[1].address, 0x100001010);
+    assert_eq!(fixups.binds[1].symbol_name, "_objc_msgSend");
+    assert!(fixups.binds[1].weak_import);
+    assert_eq!(fixups.binds[1].addend, 4);
+
+    // The pointer after the end of the chain is left alone
+    let data = file.fixed_up_section_data("data").unwrap();
+    let words: Vec<u64> = data.chunks(8).map(|x| x.iter().rev().fold(0, |a, b| a << 8 | *b as u64)).collect();
+    assert_eq!(words, vec![0x100000800, 0, 0, 0x8000000100001010, 0xdead]);
+}
+
+#[test]
+fn chained_fixups_arm64e() {
+    let file = parse("chained_arm64e");
+    let fixups = file.chained_fixups().unwrap();
+
+    assert_eq!(fixups.rebases.len(), 2);
+    assert_eq!(fixups.rebases[0].address, 0x100001000);
+    assert_eq!(fixups.rebases[0].target, 0x100000800);
+    assert!(fixups.rebases[0].auth.is_none());
+    // Authenticated rebase targets are relative to the mach header
+    assert_eq!(fixups.rebases[1].address, 0x100001008);
+    assert_eq!(fixups.rebases[1].target, 0x100000900);
+    let auth = fixups.rebases[1].auth.unwrap();
+    assert_eq!((auth.key, auth.addr_div, auth.diversity), (2, true, 0x1234));
+
+    assert_eq!(fixups.binds.len(), 2);
+    assert_eq!(fixups.binds[0].address, 0x100001010);
+    assert_eq!(fixups.binds[0].symbol_name, "_objc_msgSend");
+    assert_eq!(fixups.binds[0].library_ordinal, -2);
+    assert_eq!(fixups.binds[0].addend, -11);
+    assert!(fixups.binds[0].auth.is_none());
+    assert_eq!(fixups.binds[1].address, 0x100001018);
+    assert_eq!(fixups.binds[1].symbol_name, "_printf");
+    let auth = fixups.binds[1].auth.unwrap();
+    assert_eq!((auth.key, auth.addr_div, auth.diversity), (0, false, 7));
+}
+
+#[test]
+fn chained_fixups_unsupported_is_not_fatal() {
+    let file = parse("chained_zlib_symbols");
+    assert!(file.chained_fixups().is_none());
+    assert_eq!(file.decode_errors().len(), 1);
+    assert_eq!(file.segments().len(), 4);
+}
+
+#[test]
+fn symbol_table_is_not_fatal() {
+    let file = parse("mach_bad_symtab");
+    assert!(file.symbols().is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+#[test]
+fn thread_state_is_not_fatal() {
+    let file = parse("mach_bad_thread");
+    assert!(file.entry_command().is_none());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+#[test]
+fn load_commands_past_end_of_file() {
+    assert!(File::parse(&mut open("mach_huge_cmds")).is_err());
+}
+
+#[test]
+fn export_trie() {
+    let file = parse("export_trie");
+    assert!(file.decode_errors().is_empty());
+    let names: Vec<&str> = file.exports().iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(names, vec!["_abs", "_foo", "_foo_weak", "_ifunc", "_re", "_re2", "_tls"]);
+    let export = |name: &str| file.exports().iter().find(|x| x.name == name).unwrap();
+
+    assert_eq!(export("_foo").address, 0x100000800);
+    assert!(!export("_foo").is_weak());
+    assert_eq!(export("_foo_weak").address, 0x100000810);
+    assert!(export("_foo_weak").is_weak());
+    assert!(export("_tls").is_thread_local());
+    assert!(export("_abs").is_absolute());
+    assert_eq!(export("_abs").address, 0x1234);
+    assert_eq!(export("_ifunc").address, 0x100000820);
+    assert_eq!(export("_ifunc").resolver, Some(0x100000830));
+
+    let reexport = export("_re").reexport.as_ref().unwrap();
+    assert_eq!(reexport.library.as_ref().unwrap(), "/usr/lib/libother.dylib");
+    assert_eq!(reexport.name, "_orig");
+    // An empty name re-exports the symbol under its own name
+    assert_eq!(export("_re2").reexport.as_ref().unwrap().name, "_re2");
+}
+
+#[test]
+fn export_trie_loop_is_not_fatal() {
+    let file = parse("export_trie_loop");
+    assert!(file.exports().is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+#[test]
+fn export_trie_terminal_size_is_not_fatal() {
+    let file = parse("export_trie_terminal_size");
+    assert!(file.exports().is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+fn verify(name: &str) -> Vec<types::SignatureVerification> {
+    let mut f = open(name);
+    let file = File::parse(&mut f).unwrap();
+    file.verify_code_signature(&mut f).unwrap()
+}
+
+#[test]
+fn code_signature() {
+    let file = parse("codesign");
+    let sig = file.code_signature().unwrap();
+    assert_eq!(sig.code_directories.len(), 2);
+    let cd = &sig.code_directories[0];
+    assert_eq!(cd.identifier, "com.example.fixture");
+    assert_eq!(cd.team_id.as_ref().unwrap(), "TEAMID1234");
+    assert_eq!(cd.hash_type, types::CS_HASHTYPE_SHA1);
+    assert_eq!(cd.code_slots.len(), 2);
+    assert_eq!(sig.code_directories[1].hash_type, types::CS_HASHTYPE_SHA256);
+    assert!(sig.entitlements.as_ref().unwrap().contains("get-task-allow"));
+    assert!(sig.requirements.is_some());
+
+    let results = verify("codesign");
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|x| x.is_valid()));
+}
+
+#[test]
+fn code_signature_modified() {
+    for result in verify("codesign_modified").iter() {
+        assert_eq!(result.bad_pages, vec![1]);
+        assert!(result.bad_special_slots.is_empty());
+    }
+}
+
+#[test]
+fn code_signature_corrupt_is_not_fatal() {
+    let mut f = open("codesign_corrupt");
+    let file = File::parse(&mut f).unwrap();
+    assert!(file.code_signature().is_none());
+    assert_eq!(file.decode_errors().len(), 1);
+    assert!(file.verify_code_signature(&mut f).is_err());
+    assert_eq!(file.segments().len(), 4);
+}
+
+#[test]
+fn code_directory_zero_hash_size() {
+    // Rejected before reading any of the 2^32 - 1 empty slots
+    let file = parse("codesign_zero_hash_size");
+    assert!(file.code_signature().is_none());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+#[test]
+fn code_directory_page_size() {
+    let file = parse("codesign_page_size");
+    assert!(file.code_signature().is_none());
+    assert_eq!(file.decode_errors().len(), 1);
+}
+
+#[test]
+fn objc_metadata() {
+    let objc = parse("objc").objc_metadata().unwrap();
+
+    assert_eq!(objc.classes.len(), 1);
+    let class = &objc.classes[0];
+    assert_eq!(class.name, "MyClass");
+    assert!(class.superclass.is_none());
+    assert_eq!(class.instance_size, 16);
+    // Relative method list
+    assert_eq!(class.instance_methods.len(), 1);
+    assert_eq!(class.instance_methods[0].name, "doThing:");
+    assert_eq!(class.instance_methods[0].types, "v24@0:8@16");
+    assert_eq!(class.instance_methods[0].imp, 0x100000800);
+    // Classic method list of the metaclass
+    assert_eq!(class.class_methods.len(), 1);
+    assert_eq!(class.class_methods[0].name, "shared");
+    assert_eq!(class.class_methods[0].imp, 0x100000810);
+    assert_eq!(class.ivars.len(), 1);
+    assert_eq!((class.ivars[0].name.as_str(), class.ivars[0].offset, class.ivars[0].size), ("_count", 8, 8));
+    assert_eq!(class.protocols, vec!["MyProto"]);
+    assert_eq!(class.properties.len(), 1);
+    assert_eq!(class.properties[0].attributes, "Tq,N,V_count");
+
+    assert_eq!(objc.categories.len(), 1);
+    assert_eq!(objc.categories[0].name, "Extra");
+    assert_eq!(objc.categories[0].class.as_ref().unwrap(), "MyClass");
+    assert_eq!(objc.protocols.len(), 1);
+    assert_eq!(objc.protocols[0].name, "MyProto");
+    assert_eq!(objc.protocols[0].instance_methods[0].name, "shared");
+    assert_eq!(objc.selectors, vec!["doThing:"]);
+}
+
+#[test]
+fn objc_corrupt_method_list() {
+    assert!(parse("objc_corrupt").objc_metadata().is_err());
+}
+
+#[test]
+fn objc_wrapped_pointer() {
+    assert!(parse("objc_wrapped_pointer").objc_metadata().is_err());
+}
+
+#[test]
+fn swift_metadata() {
+    let swift = parse("swift").swift_metadata().unwrap();
+
+    assert_eq!(swift.types.len(), 1);
+    let point = &swift.types[0];
+    assert_eq!(point.qualified_name(), "MyMod.Point");
+    assert_eq!(point.fields.len(), 2);
+    assert_eq!(point.fields[0].name, "x");
+    assert_eq!(point.fields[0].mangled_type_name, "Si");
+    assert!(point.fields[0].is_var());
+    // Symbolic references are resolved to the context they point at
+    assert_eq!(point.fields[1].mangled_type_name, "MyMod.Point");
+    assert!(!point.fields[1].is_var());
+
+    assert_eq!(swift.protocols.len(), 1);
+    assert_eq!(swift.protocols[0].name, "Shape");
+    assert_eq!(swift.protocols[0].parent.as_ref().unwrap(), "MyMod");
+    assert_eq!(swift.protocols[0].num_requirements, 1);
+    assert_eq!(swift.conformances.len(), 1);
+    assert_eq!(swift.conformances[0].protocol.as_ref().unwrap(), "MyMod.Shape");
+    assert_eq!(swift.conformances[0].type_name.as_ref().unwrap(), "MyMod.Point");
+}
+
+#[test]
+fn swift_extension_cycle() {
+    let swift = parse("swift_extension_cycle").swift_metadata().unwrap();
+    assert_eq!(swift.types.len(), 2);
+    assert_eq!(swift.types[1].name, "Inner");
+    assert!(swift.types[1].parent.as_ref().unwrap().starts_with("<symbolic "));
+}
+
+#[test]
+fn swift_corrupt_field_count() {
+    assert!(parse("swift_corrupt").swift_metadata().is_err());
+}
+
+#[test]
+fn swift_wrapped_pointer() {
+    assert!(parse("swift_wrapped_pointer").swift_metadata().is_err());
+}
@@ -0,0 +1,273 @@
+extern crate execfmt;
+
+use std::fs;
+use execfmt::{ARMMode, ARMType, Arch, Endianness, FileKind, Object, RelocationKind, Section, SectionHeader,
+              SymbolBinding, SymbolKind, Width};
+
+/// Open a fixture built by tests/fixtures/mkfixtures.py
+fn open(name: &str) -> fs::File {
+    fs::File::open(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn parse(name: &str) -> Box<Object> {
+    execfmt::parse(&mut open(name)).unwrap()
+}
+
+#[test]
+fn elf_segments() {
+    let obj = parse("elf_dyn");
+    let segments: Vec<_> = obj.segments().iter()
+        .map(|x| (x.addr(), x.size(), x.offset(), x.file_size(), x.is_readable(), x.is_writable(), x.is_executable()))
+        .collect();
+    assert_eq!(segments, vec![
+        (0, 0x210, 0, 0x210, true, false, true),
+        (0x2000, 0x80, 0x1000, 0x40, true, true, false),
+    ]);
+    assert_eq!(obj.segments()[1].data().len(), 0x40);
+    assert_eq!(&obj.segments()[1].data()[..4], &[42, 0, 0, 0]);
+
+    assert_eq!(obj.read_virtual(0x200, 2).unwrap(), vec![0xc3, 0xc3]);
+    // The read runs from the end of .dynamic into .bss
+    assert_eq!(obj.read_virtual(0x2030, 0x20).unwrap(), vec![0; 0x20]);
+    assert_eq!(obj.read_virtual(0x2000, 0x80).unwrap()[..4], [42, 0, 0, 0]);
+    // Past the end of the last segment, or between segments
+    assert!(obj.read_virtual(0x2000, 0x81).is_err());
+    assert!(obj.read_virtual(0x200, 0x2000).is_err());
+    // A range that wraps around, and a huge one, are errors rather than
+    // allocations
+    assert!(obj.read_virtual(0x2000, u64::MAX).is_err());
+    assert!(obj.read_virtual(0x2000, 1 << 62).is_err());
+}
+
+#[test]
+fn pe_segments() {
+    let obj = parse("pe_dll");
+    let segments: Vec<_> = obj.segments().iter()
+        .map(|x| (x.name(), x.addr(), x.size(), x.file_size(), x.is_writable(), x.is_executable()))
+        .collect();
+    assert_eq!(segments, vec![
+        (None, 0x180000000, 0x1000, 0x400, false, false),
+        (Some(".text"), 0x180001000, 0x1000, 0x40, false, true),
+        (Some(".rdata"), 0x180002000, 0x1000, 0x400, false, false),
+        (Some(".data"), 0x180003000, 0x1000, 0x200, true, false),
+    ]);
+    assert_eq!(&obj.segments()[0].data()[..2], b"MZ");
+    // The headers are zero filled up to the section alignment
+    assert_eq!(obj.read_virtual(0x180000ffe, 4).unwrap(), vec![0, 0, 0xc3, 0xc3]);
+    assert_eq!(obj.read_virtual(0x180002200, 9).unwrap(), b"test.dll\0");
+}
+
+#[test]
+fn mach_segments() {
+    let obj = parse("export_trie");
+    let segments: Vec<_> = obj.segments().iter().map(|x| (x.name(), x.addr(), x.size())).collect();
+    assert_eq!(segments, vec![
+        (Some("__PAGEZERO"), 0, 0x100000000),
+        (Some("__TEXT"), 0x100000000, 0x1000),
+        (Some("__DATA"), 0x100001000, 0x1000),
+        (Some("__LINKEDIT"), 0x100002000, 0x1000),
+    ]);
+    assert!(obj.read_virtual(0, 4).is_err());
+    assert_eq!(obj.read_virtual(0x100000000, 4).unwrap(), vec![0xcf, 0xfa, 0xed, 0xfe]);
+    assert_eq!(obj.read_virtual(0x100000800, 2).unwrap(), vec![0xc3, 0xc3]);
+}
+
+#[test]
+fn fat_corrupt_slice() {
+    let x86_64 = Arch::X86(Width::W64);
+    let arm64 = Arch::ARM(Width::W64, Endianness::Little, ARMMode::ARM, ARMType::V8);
+    let slices = execfmt::parse_all(&mut open("fat_corrupt_slice")).unwrap();
+    assert_eq!(slices.len(), 2);
+    assert_eq!(slices[0].as_ref().unwrap().arch(), x86_64);
+    assert!(slices[1].is_err());
+    assert_eq!(parse("fat_corrupt_slice").arch(), x86_64);
+    assert_eq!(execfmt::parse_arch(&mut open("fat_corrupt_slice"), x86_64).unwrap().arch(), x86_64);
+    assert!(execfmt::parse_arch(&mut open("fat_corrupt_slice"), arm64).is_err());
+}
+
+#[test]
+fn elf_symbols() {
+    let obj = parse("elf_dyn");
+    let symbols: Vec<_> = obj.symbols().iter()
+        .map(|x| (x.name(), x.address(), x.size(), x.kind(), x.binding(), x.is_defined(), x.section()))
+        .collect();
+    assert_eq!(symbols, vec![
+        ("puts", 0, 0, SymbolKind::Text, SymbolBinding::Global, false, None),
+        ("answer", 0x2000, 4, SymbolKind::Data, SymbolBinding::Global, true, Some(".data")),
+    ]);
+}
+
+#[test]
+fn pe_symbols() {
+    // Images without a COFF symbol table get symbols from their exports,
+    // other than forwarders
+    let obj = parse("pe_dll");
+    let symbols: Vec<_> = obj.symbols().iter().map(|x| (x.name(), x.address(), x.kind(), x.section())).collect();
+    assert_eq!(symbols, vec![
+        ("alpha", 0x180001000, SymbolKind::Text, Some(".text")),
+        ("alpha2", 0x180001000, SymbolKind::Text, Some(".text")),
+        ("#2", 0x180001010, SymbolKind::Text, Some(".text")),
+    ]);
+}
+
+#[test]
+fn mach_symbols() {
+    let obj = parse("mach_symbols");
+    let symbols: Vec<_> = obj.symbols().iter()
+        .map(|x| (x.name(), x.address(), x.binding(), x.is_defined(), x.section()))
+        .collect();
+    assert_eq!(symbols, vec![
+        ("_helper", 0x100000810, SymbolBinding::Local, true, Some("__text")),
+        ("_main", 0x100000800, SymbolBinding::Global, true, Some("__text")),
+        ("_printf", 0, SymbolBinding::Global, false, None),
+    ]);
+    assert_eq!(obj.symbols()[0].kind(), SymbolKind::Text);
+}
+
+#[test]
+fn image_properties() {
+    let elf = parse("elf_dyn");
+    assert_eq!(elf.arch(), Arch::X86(Width::W64));
+    assert_eq!(elf.entry(), Some(0x200));
+    assert_eq!(elf.image_base(), 0);
+    assert_eq!(elf.kind(), FileKind::SharedLibrary);
+    assert!(elf.is_position_independent());
+
+    let pe = parse("pe_dll");
+    assert_eq!(pe.entry(), Some(0x180001000));
+    assert_eq!(pe.image_base(), 0x180000000);
+    assert_eq!(pe.kind(), FileKind::SharedLibrary);
+    assert!(pe.is_position_independent());
+
+    let mach = parse("mach_symbols");
+    assert_eq!(mach.entry(), Some(0x100000800));
+    assert_eq!(mach.image_base(), 0x100000000);
+    assert_eq!(mach.kind(), FileKind::Executable);
+    assert!(mach.is_position_independent());
+    let dylib = parse("export_trie");
+    assert_eq!(dylib.entry(), None);
+    assert_eq!(dylib.kind(), FileKind::SharedLibrary);
+}
+
+#[test]
+fn elf_imports_exports() {
+    let obj = parse("elf_dyn");
+    assert_eq!(obj.libraries(), ["libc.so.6"]);
+    let imports: Vec<_> = obj.imports().iter().map(|x| (x.library(), x.name(), x.is_weak(), x.address())).collect();
+    // Without symbol versioning an import is not tied to a library
+    assert_eq!(imports, vec![(None, Some("puts"), false, None)]);
+    let exports: Vec<_> = obj.exports().iter().map(|x| (x.name(), x.address(), x.forwarder())).collect();
+    assert_eq!(exports, vec![(Some("answer"), 0x2000, None)]);
+}
+
+#[test]
+fn mach_imports_exports() {
+    let obj = parse("mach_symbols");
+    assert_eq!(obj.libraries(), ["/usr/lib/libSystem.B.dylib"]);
+    let imports: Vec<_> = obj.imports().iter().map(|x| (x.library(), x.name())).collect();
+    assert_eq!(imports, vec![(Some("/usr/lib/libSystem.B.dylib"), Some("_printf"))]);
+    let exports: Vec<_> = obj.exports().iter().map(|x| (x.name(), x.address())).collect();
+    assert_eq!(exports, vec![(Some("_main"), 0x100000800)]);
+
+    // Bound imports have the address of the pointer they are bound to
+    let obj = parse("chained_ptr_64");
+    let imports: Vec<_> = obj.imports().iter().map(|x| (x.name(), x.is_weak(), x.address())).collect();
+    assert_eq!(imports, vec![(Some("_printf"), false, Some(0x100001008)),
+                             (Some("_objc_msgSend"), true, Some(0x100001010))]);
+
+    // Re-exports are forwarders
+    let obj = parse("export_trie");
+    let export = obj.exports().iter().find(|x| x.name() == Some("_re")).unwrap();
+    assert_eq!(export.address(), 0);
+    assert_eq!(export.forwarder(), Some("/usr/lib/libother.dylib._orig"));
+}
+
+#[test]
+fn elf_relocations() {
+    let obj = parse("elf_dyn");
+    let relocs: Vec<_> = obj.relocations().iter()
+        .map(|x| (x.address(), x.section(), x.kind(), x.raw_type(), x.symbol(), x.addend()))
+        .collect();
+    assert_eq!(relocs, vec![
+        (0x2008, Some(".data"), RelocationKind::Got, 6, Some("puts"), 0),
+        (0x2010, Some(".data"), RelocationKind::BaseRelative, 8, None, 0x200),
+    ]);
+}
+
+#[test]
+fn mach_relocations() {
+    // Chained fixups give the rebases, then the binds
+    let obj = parse("chained_ptr_64");
+    let relocs: Vec<_> = obj.relocations().iter()
+        .map(|x| (x.address(), x.kind(), x.symbol(), x.addend()))
+        .collect();
+    assert_eq!(relocs, vec![
+        (0x100001000, RelocationKind::BaseRelative, None, 0),
+        (0x100001018, RelocationKind::BaseRelative, None, 0),
+        (0x100001008, RelocationKind::Absolute, Some("_printf"), 0),
+        (0x100001010, RelocationKind::Absolute, Some("_objc_msgSend"), 4),
+    ]);
+    assert!(obj.relocations().iter().all(|x| x.section() == Some("__data")));
+}
+
+#[test]
+fn section_order() {
+    let names = |obj: &Object| obj.sections().iter().map(|x| x.name().to_string()).collect::<Vec<_>>();
+    let elf = parse("elf_dyn");
+    assert_eq!(names(&*elf), ["", ".text", ".data", ".bss", ".dynsym", ".dynstr", ".dynamic", ".rela.dyn",
+                              ".shstrtab"]);
+    // ELF section header indexes
+    assert_eq!(elf.section(3).unwrap().name(), ".bss");
+    let pe = parse("pe_dll");
+    assert_eq!(names(&*pe), [".text", ".rdata", ".data"]);
+    assert!(pe.section(3).is_none());
+}
+
+#[test]
+fn duplicate_section_names() {
+    let obj = parse("mach_duplicate_sections");
+    let sections: Vec<_> = obj.sections().iter().map(|x| (x.name(), x.addr())).collect();
+    assert_eq!(sections, vec![("__text", 0x100000800), ("__const", 0x100000840), ("__data", 0x100001000),
+                              ("__const", 0x100001040)]);
+    let consts: Vec<_> = obj.sections_by_name("__const").iter().map(|x| x.data()[0]).collect();
+    assert_eq!(consts, vec![1, 2]);
+    // get_section finds the first; Mach-O takes ELF style names there
+    assert_eq!(obj.get_section(".const").unwrap().addr(), 0x100000840);
+    // Mach-O section ordinals start at 1
+    assert_eq!(obj.section(3).unwrap().addr(), 0x100001040);
+}
+
+#[test]
+fn section_attributes() {
+    let flags = |x: &Section| (x.is_alloc(), x.is_writable(), x.is_executable(), x.is_bss(), x.align());
+    let elf = parse("elf_dyn");
+    let sections: Vec<_> = elf.sections().iter().skip(1).take(3).map(flags).collect();
+    assert_eq!(sections, vec![(true, false, true, false, 16), (true, true, false, false, 8),
+                              (true, true, false, true, 8)]);
+    assert!(elf.get_section(".bss").unwrap().data().is_empty());
+    let shstrtab = elf.get_section(".shstrtab").unwrap();
+    assert!(!shstrtab.is_alloc() && !shstrtab.is_debug());
+    match elf.get_section(".dynsym").unwrap().header() {
+        Some(&SectionHeader::Elf(ref x)) => assert_eq!((x.link, x.entsize), (5, 24)),
+        _ => panic!("expected an ELF section header"),
+    }
+
+    // Image sections are aligned to the section alignment
+    let pe = parse("pe_dll");
+    let sections: Vec<_> = pe.sections().iter().map(flags).collect();
+    assert_eq!(sections, vec![(true, false, true, false, 0x1000), (true, false, false, false, 0x1000),
+                              (true, true, false, false, 0x1000)]);
+    match pe.section(0).unwrap().header() {
+        Some(&SectionHeader::Pe(ref x)) => assert_eq!(x.characteristics, 0x60000020),
+        _ => panic!("expected a PE section header"),
+    }
+
+    let mach = parse("mach_duplicate_sections");
+    let sections: Vec<_> = mach.sections().iter().map(flags).collect();
+    assert_eq!(sections, vec![(true, false, true, false, 16), (true, false, false, false, 16),
+                              (true, true, false, false, 16), (true, true, false, false, 16)]);
+    match mach.section(0).unwrap().header() {
+        Some(&SectionHeader::Mach(ref x)) => assert_eq!((x.seg_name.as_str(), x.flags), ("__TEXT", 0x80000400)),
+        _ => panic!("expected a Mach-O section header"),
+    }
+}
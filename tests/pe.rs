@@ -0,0 +1,104 @@
+extern crate execfmt;
+
+use std::fs;
+use execfmt::Object;
+use execfmt::pe::file::File;
+
+/// Parse a fixture built by tests/fixtures/mkfixtures.py
+fn parse(name: &str) -> File {
+    let mut f = fs::File::open(format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)).unwrap();
+    File::parse(&mut f).unwrap()
+}
+
+#[test]
+fn exports() {
+    let file = parse("pe_dll");
+    assert!(file.decode_errors().is_empty());
+    let exports: Vec<_> = file.exports().iter()
+        .map(|x| (x.name(), x.ordinal(), x.address(), x.forwarder()))
+        .collect();
+    assert_eq!(exports, vec![
+        // Both names of an aliased function are exported
+        (Some("alpha"), Some(1), 0x180001000, None),
+        (Some("alpha2"), Some(1), 0x180001000, None),
+        (None, Some(2), 0x180001010, None),
+        (Some("fwd"), Some(3), 0, Some("KERNEL32.Sleep")),
+    ]);
+}
+
+#[test]
+fn imports() {
+    let file = parse("pe_dll");
+    assert_eq!(file.libraries(), ["KERNEL32.dll", "USER32.dll"]);
+    let imports: Vec<_> = file.imports().iter()
+        .map(|x| (x.library(), x.name(), x.ordinal(), x.is_delayed(), x.address()))
+        .collect();
+    assert_eq!(imports, vec![
+        (Some("KERNEL32.dll"), Some("Sleep"), None, false, Some(0x180002260)),
+        (Some("KERNEL32.dll"), None, Some(17), false, Some(0x180002268)),
+        (Some("USER32.dll"), Some("MessageBoxA"), None, true, Some(0x180003000)),
+    ]);
+    assert_eq!(file.imports[0].hint, 5);
+}
+
+#[test]
+fn bad_export_ordinals_are_not_fatal() {
+    let file = parse("pe_bad_export_ordinals");
+    assert!(file.exports().is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+    assert_eq!(file.imports().len(), 3);
+}
+
+#[test]
+fn bad_import_hint_is_not_fatal() {
+    let file = parse("pe_bad_import_hint");
+    assert_eq!(file.decode_errors().len(), 1);
+    // The delay import directory is decoded on its own
+    assert_eq!(file.libraries(), ["USER32.dll"]);
+    assert_eq!(file.exports().len(), 4);
+}
+
+#[test]
+fn bad_symbol_table_is_not_fatal() {
+    let file = parse("pe_bad_symtab");
+    assert!(file.symtab.is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+    // Exports still provide symbols
+    assert_eq!(file.symbols().len(), 3);
+    assert_eq!(file.exports().len(), 4);
+}
+
+#[test]
+fn base_relocations() {
+    let file = parse("pe_base_relocs");
+    assert!(file.decode_errors().is_empty());
+    let relocs: Vec<_> = file.relocations().iter()
+        .map(|x| (x.address(), x.section(), x.raw_type()))
+        .collect();
+    // The ABSOLUTE padding entry is skipped
+    assert_eq!(relocs, vec![
+        (0x180003010, Some(".data"), 10),
+        (0x180003018, Some(".data"), 10),
+        (0x180002008, Some(".rdata"), 3),
+    ]);
+    assert!(file.relocations().iter().all(|x| x.kind() == execfmt::RelocationKind::BaseRelative));
+}
+
+#[test]
+fn bad_base_relocations_are_not_fatal() {
+    let file = parse("pe_bad_base_relocs");
+    assert!(file.relocations().is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+    assert_eq!(file.sections().len(), 3);
+}
+
+#[test]
+fn coff_relocations() {
+    let file = parse("pe_coff_relocs");
+    assert_eq!(file.section_relocs[0].len(), 2);
+    let relocs: Vec<_> = file.relocations().iter().map(|x| (x.address(), x.section(), x.raw_type())).collect();
+    assert_eq!(relocs, vec![(0x180001008, Some(".text"), 1), (0x180001010, Some(".text"), 1)]);
+    // The .data table runs past the end of the file
+    assert!(file.section_relocs[2].is_empty());
+    assert_eq!(file.decode_errors().len(), 1);
+}